@@ -1,4 +1,9 @@
 fn main() {
+    // espeak-ng is only linked when the `espeak` feature is enabled
+    if std::env::var_os("CARGO_FEATURE_ESPEAK").is_none() {
+        return;
+    }
+
     // Link espeak-ng library
     if cfg!(target_os = "macos") {
         // For macOS with Homebrew
@@ -0,0 +1,73 @@
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Pronunciation dictionary in CMUdict format, mapping lowercase words to
+/// ARPAbet phones with stress digits.
+///
+/// Both the current `cmudict.dict` layout (`hello HH AH0 L OW1`, alternates
+/// written `hello(2)`, `#` comments) and the older `cmudict-0.7b` layout
+/// (uppercase words, `;;;` comments, Latin-1 encoded) are accepted. Only the
+/// first pronunciation of each word is kept.
+#[derive(Clone, Default)]
+pub struct CmuDict {
+    entries: HashMap<String, String>,
+}
+
+impl CmuDict {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| Error::io(path, e))?;
+        let content = match String::from_utf8(bytes) {
+            Ok(content) => content,
+            // cmudict-0.7b is Latin-1; every byte maps to the same code point
+            Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+        };
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut dictionary = Self::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() || line.starts_with(";;;") {
+                continue;
+            }
+
+            let (word, phones) = line.split_once(char::is_whitespace).ok_or_else(|| {
+                Error::g2p(format!(
+                    "Invalid CMUdict entry on line {}: expected 'WORD PHONES'",
+                    line_number + 1
+                ))
+            })?;
+
+            // Alternate pronunciations: "word(2)"
+            let word = match word.find('(') {
+                Some(index) if word.ends_with(')') => &word[..index],
+                _ => word,
+            };
+
+            let phones = phones.split_whitespace().collect::<Vec<_>>().join(" ");
+            dictionary
+                .entries
+                .entry(word.to_lowercase())
+                .or_insert(phones);
+        }
+
+        if dictionary.entries.is_empty() {
+            return Err(Error::g2p("CMUdict file contains no entries"));
+        }
+
+        Ok(dictionary)
+    }
+
+    /// ARPAbet phones for a word (case-insensitive), e.g. `"HH AH0 L OW1"`.
+    pub fn lookup(&self, word: &str) -> Option<&str> {
+        self.entries.get(&word.to_lowercase()).map(String::as_str)
+    }
+}
@@ -0,0 +1,594 @@
+/// Letter-to-sound rules from the NRL report "Automatic Translation of English
+/// Text to Phonetics by Means of Letter-to-Sound Rules" (Elovitz et al., 1976),
+/// with phones written in ARPAbet. `AX` marks a reduced vowel.
+///
+/// Each rule is `(left context, letters, right context, phones)` and the first
+/// rule whose letters and contexts match wins. Context patterns use:
+///
+/// - ` ` a word boundary
+/// - `#` one or more vowels
+/// - `:` zero or more consonants
+/// - `^` one consonant
+/// - `.` one voiced consonant (B D V G J L M N R W Z)
+/// - `+` one front vowel (E I Y)
+/// - `%` one suffix (E, ER, ES, ED, ING, ELY)
+/// - `@` one of T S R D L Z N J TH CH SH
+///
+/// An empty context matches anything.
+#[rustfmt::skip]
+const RULES: &[(&str, &str, &str, &str)] = &[
+    // A
+    ("", "A", " ", "AX"),
+    (" ", "ARE", " ", "AA R"),
+    (" ", "AR", "O", "AX R"),
+    ("", "AR", "#", "EH R"),
+    ("^", "AS", "#", "EY S"),
+    ("", "A", "WA", "AX"),
+    ("", "AW", "", "AO"),
+    (" :", "ANY", "", "EH N IY"),
+    ("", "A", "^+#", "EY"),
+    ("#:", "ALLY", "", "AX L IY"),
+    (" ", "AL", "#", "AX L"),
+    ("", "AGAIN", "", "AX G EH N"),
+    ("#:", "AG", "E", "IH JH"),
+    ("", "A", "^+:#", "AE"),
+    (" :", "A", "^+ ", "EY"),
+    ("", "A", "^%", "EY"),
+    (" ", "ARR", "", "AX R"),
+    ("", "ARR", "", "AE R"),
+    (" :", "AR", " ", "AA R"),
+    ("", "AR", " ", "ER"),
+    ("", "AR", "", "AA R"),
+    ("", "AIR", "", "EH R"),
+    ("", "AI", "", "EY"),
+    ("", "AY", "", "EY"),
+    ("", "AU", "", "AO"),
+    ("#:", "AL", " ", "AX L"),
+    ("#:", "ALS", " ", "AX L Z"),
+    ("", "ALK", "", "AO K"),
+    ("", "AL", "^", "AO L"),
+    (" :", "ABLE", "", "EY B AX L"),
+    ("", "ABLE", "", "AX B AX L"),
+    ("", "ANG", "+", "EY N JH"),
+    ("", "A", "", "AE"),
+    // B
+    (" ", "BE", "^#", "B IH"),
+    ("", "BEING", "", "B IY IH NG"),
+    (" ", "BOTH", " ", "B OW TH"),
+    (" ", "BUS", "#", "B IH Z"),
+    ("", "BUIL", "", "B IH L"),
+    ("", "B", "", "B"),
+    // C
+    (" ", "CH", "^", "K"),
+    ("^E", "CH", "", "K"),
+    ("", "CH", "", "CH"),
+    (" S", "CI", "#", "S AY"),
+    ("", "CI", "A", "SH"),
+    ("", "CI", "O", "SH"),
+    ("", "CI", "EN", "SH"),
+    ("", "C", "+", "S"),
+    ("", "CK", "", "K"),
+    ("", "COM", "%", "K AH M"),
+    ("", "C", "", "K"),
+    // D
+    ("#:", "DED", " ", "D IH D"),
+    (".E", "D", " ", "D"),
+    ("#:^E", "D", " ", "T"),
+    (" ", "DE", "^#", "D IH"),
+    (" ", "DO", " ", "D UW"),
+    (" ", "DOES", "", "D AH Z"),
+    (" ", "DOING", "", "D UW IH NG"),
+    (" ", "DOW", "", "D AW"),
+    ("", "DU", "A", "JH UW"),
+    ("", "D", "", "D"),
+    // E
+    ("#:", "E", " ", ""),
+    (" :", "E", " ", "IY"),
+    ("#", "ED", " ", "D"),
+    ("#:", "E", "D ", ""),
+    ("", "EV", "ER", "EH V"),
+    ("", "E", "^%", "IY"),
+    ("", "ERI", "#", "IY R IY"),
+    ("", "ERI", "", "EH R IH"),
+    ("#:", "ER", "#", "ER"),
+    ("", "ER", "#", "EH R"),
+    ("", "ER", "", "ER"),
+    (" ", "EVEN", "", "IY V EH N"),
+    ("#:", "E", "W", ""),
+    ("@", "EW", "", "UW"),
+    ("", "EW", "", "Y UW"),
+    ("", "E", "O", "IY"),
+    ("#:S", "ES", " ", "IH Z"),
+    ("#:C", "ES", " ", "IH Z"),
+    ("#:G", "ES", " ", "IH Z"),
+    ("#:Z", "ES", " ", "IH Z"),
+    ("#:X", "ES", " ", "IH Z"),
+    ("#:J", "ES", " ", "IH Z"),
+    ("#:CH", "ES", " ", "IH Z"),
+    ("#:SH", "ES", " ", "IH Z"),
+    ("#:", "E", "S ", ""),
+    ("#:", "ELY", " ", "L IY"),
+    ("#:", "EMENT", "", "M EH N T"),
+    ("", "EFUL", "", "F UH L"),
+    ("", "EE", "", "IY"),
+    ("", "EARN", "", "ER N"),
+    (" ", "EAR", "^", "ER"),
+    ("", "EAD", "", "EH D"),
+    ("#:", "EA", " ", "IY AX"),
+    ("", "EA", "SU", "EH"),
+    ("", "EA", "", "IY"),
+    ("", "EIGH", "", "EY"),
+    ("", "EI", "", "IY"),
+    (" ", "EYE", "", "AY"),
+    ("", "EY", "", "IY"),
+    ("", "EU", "", "Y UW"),
+    ("", "E", "", "EH"),
+    // F
+    ("", "FUL", "", "F UH L"),
+    ("", "F", "", "F"),
+    // G
+    ("", "GIV", "", "G IH V"),
+    (" ", "G", "I^", "G"),
+    ("", "GE", "T", "G EH"),
+    ("SU", "GGES", "", "G JH EH S"),
+    ("", "GG", "", "G"),
+    (" B#", "G", "", "G"),
+    ("", "G", "+", "JH"),
+    ("", "GREAT", "", "G R EY T"),
+    ("#", "GH", "", ""),
+    ("", "G", "", "G"),
+    // H
+    (" ", "HAV", "", "HH AE V"),
+    (" ", "HERE", "", "HH IY R"),
+    (" ", "HOUR", "", "AW ER"),
+    ("", "HOW", "", "HH AW"),
+    ("", "H", "#", "HH"),
+    ("", "H", "", ""),
+    // I
+    (" ", "IN", "", "IH N"),
+    (" ", "I", " ", "AY"),
+    ("", "IN", "D", "AY N"),
+    ("", "IER", "", "IY ER"),
+    ("#:R", "IED", "", "IY D"),
+    ("", "IED", " ", "AY D"),
+    ("", "IEN", "", "IY EH N"),
+    ("", "IE", "T", "AY EH"),
+    (" :", "I", "%", "AY"),
+    ("", "I", "%", "IY"),
+    ("", "IE", "", "IY"),
+    ("", "I", "^+:#", "IH"),
+    ("", "IR", "#", "AY R"),
+    ("", "IZ", "%", "AY Z"),
+    ("", "IS", "%", "AY Z"),
+    ("", "I", "D%", "AY"),
+    ("+^", "I", "^+", "IH"),
+    ("", "I", "T%", "AY"),
+    ("#:^", "I", "^+", "IH"),
+    ("", "I", "^+", "AY"),
+    ("", "IR", "", "ER"),
+    ("", "IGH", "", "AY"),
+    ("", "ILD", "", "AY L D"),
+    ("", "IGN", " ", "AY N"),
+    ("", "IGN", "^", "AY N"),
+    ("", "IGN", "%", "AY N"),
+    ("", "IQUE", "", "IY K"),
+    ("", "I", "", "IH"),
+    // J
+    ("", "J", "", "JH"),
+    // K
+    (" ", "K", "N", ""),
+    ("", "K", "", "K"),
+    // L
+    ("", "LO", "C#", "L OW"),
+    ("L", "L", "", ""),
+    ("#:^", "L", "%", "AX L"),
+    ("", "LEAD", "", "L IY D"),
+    ("", "L", "", "L"),
+    // M
+    ("", "MOV", "", "M UW V"),
+    ("", "M", "", "M"),
+    // N
+    ("E", "NG", "+", "N JH"),
+    ("", "NG", "R", "NG G"),
+    ("", "NG", "#", "NG G"),
+    ("", "NGL", "%", "NG G AX L"),
+    ("", "NG", "", "NG"),
+    ("", "NK", "", "NG K"),
+    (" ", "NOW", " ", "N AW"),
+    ("", "N", "", "N"),
+    // O
+    ("", "OF", " ", "AX V"),
+    ("", "OROUGH", "", "ER OW"),
+    ("#:", "OR", " ", "ER"),
+    ("#:", "ORS", " ", "ER Z"),
+    ("", "OR", "", "AO R"),
+    (" ", "ONE", "", "W AH N"),
+    ("", "OW", "", "OW"),
+    (" ", "OVER", "", "OW V ER"),
+    ("", "OV", "", "AH V"),
+    ("", "O", "^%", "OW"),
+    ("", "O", "^EN", "OW"),
+    ("", "O", "^I#", "OW"),
+    ("", "OL", "D", "OW L"),
+    ("", "OUGHT", "", "AO T"),
+    ("", "OUGH", "", "AH F"),
+    (" ", "OU", "", "AW"),
+    ("H", "OU", "S#", "AW"),
+    ("", "OUS", "", "AX S"),
+    ("", "OUR", "", "AO R"),
+    ("", "OULD", "", "UH D"),
+    ("^", "OU", "^L", "AH"),
+    ("", "OUP", "", "UW P"),
+    ("", "OU", "", "AW"),
+    ("", "OY", "", "OY"),
+    ("", "OING", "", "OW IH NG"),
+    ("", "OI", "", "OY"),
+    ("", "OOR", "", "AO R"),
+    ("", "OOK", "", "UH K"),
+    ("", "OOD", "", "UH D"),
+    ("", "OO", "", "UW"),
+    ("", "O", "E", "OW"),
+    ("", "O", " ", "OW"),
+    ("", "OA", "", "OW"),
+    (" ", "ONLY", "", "OW N L IY"),
+    (" ", "ONCE", "", "W AH N S"),
+    ("", "ON'T", "", "OW N T"),
+    ("C", "O", "N", "AA"),
+    ("", "O", "NG", "AO"),
+    (" :^", "O", "N", "AH"),
+    ("I", "ON", "", "AX N"),
+    ("#:", "ON", " ", "AX N"),
+    ("#^", "ON", "", "AX N"),
+    ("", "O", "ST ", "OW"),
+    ("", "OF", "^", "AO F"),
+    ("", "OTHER", "", "AH DH ER"),
+    ("", "OSS", " ", "AO S"),
+    ("#:^", "OM", "", "AH M"),
+    ("", "O", "", "AA"),
+    // P
+    ("", "PH", "", "F"),
+    ("", "PEOP", "", "P IY P"),
+    ("", "POW", "", "P AW"),
+    ("", "PUT", " ", "P UH T"),
+    ("", "P", "", "P"),
+    // Q
+    ("", "QUAR", "", "K W AO R"),
+    ("", "QU", "", "K W"),
+    ("", "Q", "", "K"),
+    // R
+    (" ", "RE", "^#", "R IY"),
+    ("", "R", "", "R"),
+    // S
+    ("", "SH", "", "SH"),
+    ("#", "SION", "", "ZH AX N"),
+    ("", "SOME", "", "S AH M"),
+    ("#", "SUR", "#", "ZH ER"),
+    ("", "SUR", "#", "SH ER"),
+    ("#", "SU", "#", "ZH UW"),
+    ("#", "SSU", "#", "SH UW"),
+    ("#", "SED", " ", "Z D"),
+    ("#", "S", "#", "Z"),
+    ("", "SAID", "", "S EH D"),
+    ("^", "SION", "", "SH AX N"),
+    ("", "S", "S", ""),
+    (".", "S", " ", "Z"),
+    ("#:.E", "S", " ", "Z"),
+    ("#:^##", "S", " ", "Z"),
+    ("#:^#", "S", " ", "S"),
+    ("U", "S", " ", "S"),
+    (" :#", "S", " ", "Z"),
+    (" ", "SCH", "", "S K"),
+    ("", "S", "C+", ""),
+    ("#", "SM", "", "Z M"),
+    ("#", "SN", "'", "Z AX N"),
+    ("", "S", "", "S"),
+    // T
+    (" ", "THE", " ", "DH AX"),
+    ("", "TO", " ", "T UW"),
+    ("", "THAT", " ", "DH AE T"),
+    (" ", "THIS", " ", "DH IH S"),
+    (" ", "THEY", "", "DH EY"),
+    (" ", "THERE", "", "DH EH R"),
+    ("", "THER", "", "DH ER"),
+    ("", "THEIR", "", "DH EH R"),
+    (" ", "THAN", " ", "DH AE N"),
+    (" ", "THEM", " ", "DH EH M"),
+    ("", "THESE", " ", "DH IY Z"),
+    (" ", "THEN", "", "DH EH N"),
+    ("", "THROUGH", "", "TH R UW"),
+    ("", "THOSE", "", "DH OW Z"),
+    ("", "THOUGH", " ", "DH OW"),
+    (" ", "THUS", "", "DH AH S"),
+    ("", "TH", "", "TH"),
+    ("#:", "TED", " ", "T IH D"),
+    ("S", "TI", "#N", "CH"),
+    ("", "TI", "O", "SH"),
+    ("", "TI", "A", "SH"),
+    ("", "TIEN", "", "SH AX N"),
+    ("", "TUR", "#", "CH ER"),
+    ("", "TU", "A", "CH UW"),
+    (" ", "TWO", "", "T UW"),
+    ("", "T", "", "T"),
+    // U
+    (" ", "UN", "I", "Y UW N"),
+    (" ", "UN", "", "AH N"),
+    (" ", "UPON", "", "AX P AO N"),
+    ("@", "UR", "#", "UH R"),
+    ("", "UR", "#", "Y UH R"),
+    ("", "UR", "", "ER"),
+    ("", "U", "^ ", "AH"),
+    ("", "U", "^^", "AH"),
+    ("", "UY", "", "AY"),
+    (" G", "U", "#", ""),
+    ("G", "U", "%", ""),
+    ("G", "U", "#", "W"),
+    ("#N", "U", "", "Y UW"),
+    ("@", "U", "", "UW"),
+    ("", "U", "", "Y UW"),
+    // V
+    ("", "VIEW", "", "V Y UW"),
+    ("", "V", "", "V"),
+    // W
+    (" ", "WERE", "", "W ER"),
+    ("", "WA", "S", "W AA"),
+    ("", "WA", "T", "W AA"),
+    ("", "WHERE", "", "W EH R"),
+    ("", "WHAT", "", "W AA T"),
+    ("", "WHOL", "", "HH OW L"),
+    ("", "WHO", "", "HH UW"),
+    ("", "WH", "", "W"),
+    ("", "WAR", "", "W AO R"),
+    ("", "WOR", "^", "W ER"),
+    ("", "WR", "", "R"),
+    ("", "W", "", "W"),
+    // X
+    ("", "X", "", "K S"),
+    // Y
+    ("", "YOUNG", "", "Y AH NG"),
+    (" ", "YOU", "", "Y UW"),
+    (" ", "YES", "", "Y EH S"),
+    (" ", "Y", "", "Y"),
+    ("#:^", "Y", " ", "IY"),
+    ("#:^", "Y", "I", "IY"),
+    (" :", "Y", " ", "AY"),
+    (" :", "Y", "#", "AY"),
+    (" :", "Y", "^+:#", "IH"),
+    (" :", "Y", "^#", "AY"),
+    ("", "Y", "", "IH"),
+    // Z
+    ("", "Z", "", "Z"),
+];
+
+/// ARPAbet vowels produced by the rules (before stress digits are added)
+const VOWELS: &[&str] = &[
+    "AA", "AE", "AH", "AO", "AW", "AX", "AY", "EH", "ER", "EY", "IH", "IY", "OW", "OY", "UH", "UW",
+];
+
+/// Predict ARPAbet phones with stress digits for a word missing from the
+/// dictionary. The first full (non-`AX`) vowel receives primary stress.
+pub fn letters_to_arpabet(word: &str) -> Vec<String> {
+    // Pad with word boundaries so contexts can look one character past the ends
+    let padded: Vec<char> = format!(" {} ", word.to_uppercase()).chars().collect();
+    let mut phones: Vec<&str> = Vec::new();
+    let mut i = 1;
+
+    while i < padded.len() - 1 {
+        let rule = RULES.iter().find(|(left, letters, right, _)| {
+            matches_letters(&padded, i, letters)
+                && matches_left(&padded[..i], left)
+                && matches_right(&padded[i + letters.chars().count()..], right)
+        });
+
+        match rule {
+            Some((_, letters, _, output)) => {
+                phones.extend(output.split_whitespace());
+                i += letters.chars().count();
+            }
+            // Apostrophes and anything the rules don't cover are silent
+            None => i += 1,
+        }
+    }
+
+    // Doubled consonant letters ("running") are pronounced once
+    phones.dedup_by(|a, b| a == b && !VOWELS.contains(a));
+
+    assign_stress(&phones)
+}
+
+fn assign_stress(phones: &[&str]) -> Vec<String> {
+    let stressed = phones
+        .iter()
+        .position(|phone| VOWELS.contains(phone) && *phone != "AX");
+
+    phones
+        .iter()
+        .enumerate()
+        .map(|(index, &phone)| {
+            if phone == "AX" {
+                "AH0".to_string()
+            } else if VOWELS.contains(&phone) {
+                let digit = if Some(index) == stressed { 1 } else { 0 };
+                format!("{}{}", phone, digit)
+            } else {
+                phone.to_string()
+            }
+        })
+        .collect()
+}
+
+fn matches_letters(word: &[char], start: usize, letters: &str) -> bool {
+    letters
+        .chars()
+        .enumerate()
+        .all(|(offset, letter)| word.get(start + offset) == Some(&letter))
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'A' | 'E' | 'I' | 'O' | 'U')
+}
+
+fn is_consonant(c: char) -> bool {
+    c.is_ascii_uppercase() && !is_vowel(c)
+}
+
+fn is_voiced(c: char) -> bool {
+    matches!(
+        c,
+        'B' | 'D' | 'V' | 'G' | 'J' | 'L' | 'M' | 'N' | 'R' | 'W' | 'Z'
+    )
+}
+
+fn is_front_vowel(c: char) -> bool {
+    matches!(c, 'E' | 'I' | 'Y')
+}
+
+fn is_boundary(c: char) -> bool {
+    !c.is_ascii_uppercase() && c != '\''
+}
+
+/// Match a left context, reading the pattern and the text right to left.
+fn matches_left(text: &[char], pattern: &str) -> bool {
+    let mut pos = text.len();
+
+    for symbol in pattern.chars().rev() {
+        let current = |pos: usize| if pos == 0 { ' ' } else { text[pos - 1] };
+
+        match symbol {
+            '#' => {
+                if !is_vowel(current(pos)) {
+                    return false;
+                }
+                while pos > 0 && is_vowel(current(pos)) {
+                    pos -= 1;
+                }
+            }
+            ':' => {
+                while pos > 0 && is_consonant(current(pos)) {
+                    pos -= 1;
+                }
+            }
+            '^' | '.' | '+' => {
+                let c = current(pos);
+                let ok = match symbol {
+                    '^' => is_consonant(c),
+                    '.' => is_voiced(c),
+                    _ => is_front_vowel(c),
+                };
+                if !ok || pos == 0 {
+                    return false;
+                }
+                pos -= 1;
+            }
+            '@' => {
+                let c = current(pos);
+                if pos == 0 || !is_consonant(c) {
+                    return false;
+                }
+                if c == 'H' {
+                    if pos < 2 || !matches!(text[pos - 2], 'T' | 'C' | 'S') {
+                        return false;
+                    }
+                    pos -= 1;
+                } else if !"TSRDLZNJ".contains(c) {
+                    return false;
+                }
+                pos -= 1;
+            }
+            ' ' => {
+                if !is_boundary(current(pos)) {
+                    return false;
+                }
+                pos = pos.saturating_sub(1);
+            }
+            literal => {
+                if pos == 0 || text[pos - 1] != literal {
+                    return false;
+                }
+                pos -= 1;
+            }
+        }
+    }
+
+    true
+}
+
+/// Match a right context, reading the pattern and the text left to right.
+fn matches_right(text: &[char], pattern: &str) -> bool {
+    let mut pos = 0;
+    let at = |pos: usize| text.get(pos).copied().unwrap_or(' ');
+
+    for symbol in pattern.chars() {
+        match symbol {
+            '#' => {
+                if !is_vowel(at(pos)) {
+                    return false;
+                }
+                while is_vowel(at(pos)) {
+                    pos += 1;
+                }
+            }
+            ':' => {
+                while is_consonant(at(pos)) {
+                    pos += 1;
+                }
+            }
+            '^' | '.' | '+' => {
+                let c = at(pos);
+                let ok = match symbol {
+                    '^' => is_consonant(c),
+                    '.' => is_voiced(c),
+                    _ => is_front_vowel(c),
+                };
+                if !ok {
+                    return false;
+                }
+                pos += 1;
+            }
+            '%' => match suffix_length(&text[pos.min(text.len())..]) {
+                Some(length) => pos += length,
+                None => return false,
+            },
+            '@' => {
+                let c = at(pos);
+                if "TSRDLZNJ".contains(c) && is_consonant(c) {
+                    pos += 1;
+                } else if matches!(c, 'T' | 'C' | 'S') && at(pos + 1) == 'H' {
+                    pos += 2;
+                } else {
+                    return false;
+                }
+            }
+            ' ' => {
+                if !is_boundary(at(pos)) {
+                    return false;
+                }
+                pos += 1;
+            }
+            literal => {
+                if at(pos) != literal {
+                    return false;
+                }
+                pos += 1;
+            }
+        }
+    }
+
+    true
+}
+
+/// Length of a suffix matched by `%`: ER, ES, ED, ELY, ING or a lone E
+fn suffix_length(text: &[char]) -> Option<usize> {
+    let starts_with = |suffix: &str| {
+        suffix.chars().count() <= text.len() && suffix.chars().zip(text).all(|(a, &b)| a == b)
+    };
+
+    if starts_with("ELY") || starts_with("ING") {
+        Some(3)
+    } else if starts_with("ER") || starts_with("ES") || starts_with("ED") {
+        Some(2)
+    } else if starts_with("E") {
+        Some(1)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,6 @@
+pub mod dictionary;
+pub mod letter_to_sound;
+pub mod phonemizer;
+
+pub use dictionary::CmuDict;
+pub use phonemizer::CmuDictG2P;
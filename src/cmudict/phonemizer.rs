@@ -0,0 +1,163 @@
+use super::letter_to_sound::letters_to_arpabet;
+use super::CmuDict;
+use crate::error::{Error, Result};
+use crate::phonemes::arpabet::phone_to_misaki;
+
+/// Punctuation kept in the phoneme string (Kokoro uses it for phrasing)
+const PUNCTUATION: &[char] = &[',', '.', '!', '?', ';', ':', '—', '…'];
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const SCALES: [(u64, &str); 3] = [
+    (1_000_000_000, "billion"),
+    (1_000_000, "million"),
+    (1_000, "thousand"),
+];
+
+/// Pure-Rust English G2P: CMUdict lookups with NRL letter-to-sound rules for
+/// out-of-vocabulary words, producing Misaki phonemes like the espeak-ng path.
+pub struct CmuDictG2P {
+    dictionary: CmuDict,
+}
+
+impl CmuDictG2P {
+    pub fn new(dictionary: CmuDict) -> Self {
+        CmuDictG2P { dictionary }
+    }
+
+    /// Convert English text to Misaki phonemes, one phoneme word per input
+    /// word, with sentence punctuation attached to the preceding word.
+    pub fn phonemize(&self, text: &str) -> Result<String> {
+        let mut result = String::with_capacity(text.len() * 2);
+        let mut word = String::new();
+
+        for c in text.chars() {
+            if c.is_alphanumeric() || ((c == '\'' || c == '’') && !word.is_empty()) {
+                word.push(if c == '’' { '\'' } else { c });
+                continue;
+            }
+
+            self.push_word(&mut result, &word);
+            word.clear();
+
+            if PUNCTUATION.contains(&c) {
+                result.push(c);
+            }
+        }
+        self.push_word(&mut result, &word);
+
+        if result.is_empty() {
+            return Err(Error::g2p(format!("No phonemes produced for '{}'", text)));
+        }
+
+        Ok(result)
+    }
+
+    /// ARPAbet phones for one word: the dictionary entry, or letter-to-sound
+    /// rules for words it doesn't know.
+    pub fn word_to_arpabet(&self, word: &str) -> Vec<String> {
+        if let Some(phones) = self.dictionary.lookup(word) {
+            return phones.split_whitespace().map(str::to_string).collect();
+        }
+
+        let trimmed = word.trim_end_matches('\'');
+        if trimmed != word {
+            return self.word_to_arpabet(trimmed);
+        }
+
+        letters_to_arpabet(word)
+    }
+
+    fn push_word(&self, result: &mut String, word: &str) {
+        if word.is_empty() {
+            return;
+        }
+        if word.chars().all(|c| c.is_ascii_digit()) {
+            for number_word in number_to_words(word).split_whitespace() {
+                self.push_word(result, number_word);
+            }
+            return;
+        }
+
+        if !result.is_empty() {
+            result.push(' ');
+        }
+        for phone in self.word_to_arpabet(word) {
+            // Phones come from the dictionary or the rules; skip anything unknown
+            if let Some(phonemes) = phone_to_misaki(&phone) {
+                result.push_str(&phonemes);
+            }
+        }
+    }
+}
+
+/// Spell out a string of digits ("1984" -> "one thousand nine hundred eighty four").
+/// Numbers too large for the scale words are read digit by digit.
+fn number_to_words(digits: &str) -> String {
+    match digits.parse::<u64>() {
+        Ok(n) if n < 1_000_000_000_000 => integer_to_words(n),
+        _ => digits
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(|d| ONES[d as usize])
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+fn integer_to_words(n: u64) -> String {
+    if n < 20 {
+        return ONES[n as usize].to_string();
+    }
+    if n < 100 {
+        let tens = TENS[(n / 10) as usize];
+        return match n % 10 {
+            0 => tens.to_string(),
+            ones => format!("{} {}", tens, ONES[ones as usize]),
+        };
+    }
+    if n < 1_000 {
+        let hundreds = format!("{} hundred", ONES[(n / 100) as usize]);
+        return match n % 100 {
+            0 => hundreds,
+            rest => format!("{} {}", hundreds, integer_to_words(rest)),
+        };
+    }
+
+    for (scale, name) in SCALES {
+        if n >= scale {
+            let head = format!("{} {}", integer_to_words(n / scale), name);
+            return match n % scale {
+                0 => head,
+                rest => format!("{} {}", head, integer_to_words(rest)),
+            };
+        }
+    }
+
+    unreachable!("numbers below 1000 are handled above")
+}
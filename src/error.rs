@@ -0,0 +1,203 @@
+use crate::phonemes::PhonemeConversionError;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Boxed underlying error carried by `Error` variants
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Result alias used throughout kokoroxide's public API
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors returned by kokoroxide. Each variant names the stage that failed;
+/// the underlying error, when there is one, is available through `source()`.
+#[derive(Debug)]
+pub enum Error {
+    /// Invalid configuration or arguments (unsupported voice for the G2P
+    /// backend, missing feature, nothing to synthesize)
+    Config {
+        message: String,
+        source: Option<BoxError>,
+    },
+    /// A model, tokenizer, voice, dictionary or output file could not be
+    /// read or written
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// `tokenizer.json` could not be parsed or lacks a required token
+    Tokenizer {
+        message: String,
+        source: Option<BoxError>,
+    },
+    /// Text could not be converted to phonemes (espeak-ng, CMUdict, Japanese,
+    /// or a malformed pronunciation dictionary)
+    G2p {
+        message: String,
+        source: Option<BoxError>,
+    },
+    /// IPA, ARPAbet or X-SAMPA input contains symbols with no Misaki equivalent
+    PhonemeConversion(PhonemeConversionError),
+    /// A voice style file or vector has the wrong size or layout
+    VoiceFormat {
+        message: String,
+        source: Option<BoxError>,
+    },
+    /// The ONNX model's inputs or outputs don't match any known Kokoro export
+    UnsupportedModel {
+        message: String,
+        source: Option<BoxError>,
+    },
+    /// ONNX Runtime failed to create the session or run the model
+    Inference {
+        message: String,
+        source: Option<BoxError>,
+    },
+    /// Model output could not be extracted or encoded as audio
+    OutputExtraction {
+        message: String,
+        source: Option<BoxError>,
+    },
+    /// A `KokoroPool` rejected the request because `limit` requests were
+    /// already waiting for a session
+    QueueFull { limit: usize },
+}
+
+impl Error {
+    pub(crate) fn config(message: impl Into<String>) -> Self {
+        Error::Config {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Error::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    pub(crate) fn tokenizer(message: impl Into<String>) -> Self {
+        Error::Tokenizer {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn g2p(message: impl Into<String>) -> Self {
+        Error::G2p {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn voice_format(message: impl Into<String>) -> Self {
+        Error::VoiceFormat {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn unsupported_model(message: impl Into<String>) -> Self {
+        Error::UnsupportedModel {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn inference(message: impl Into<String>) -> Self {
+        Error::Inference {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn output(message: impl Into<String>) -> Self {
+        Error::OutputExtraction {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Attach the underlying error to a message-carrying variant.
+    pub(crate) fn with_source(mut self, error: impl Into<BoxError>) -> Self {
+        match &mut self {
+            Error::Config { source, .. }
+            | Error::Tokenizer { source, .. }
+            | Error::G2p { source, .. }
+            | Error::VoiceFormat { source, .. }
+            | Error::UnsupportedModel { source, .. }
+            | Error::Inference { source, .. }
+            | Error::OutputExtraction { source, .. } => *source = Some(error.into()),
+            Error::Io { .. } | Error::PhonemeConversion(_) | Error::QueueFull { .. } => {}
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (stage, message, source) = match self {
+            Error::Config { message, source } => ("Configuration error", message, source),
+            Error::Tokenizer { message, source } => ("Tokenizer error", message, source),
+            Error::G2p { message, source } => ("Phonemization failed", message, source),
+            Error::VoiceFormat { message, source } => ("Invalid voice style", message, source),
+            Error::UnsupportedModel { message, source } => {
+                ("Unsupported model signature", message, source)
+            }
+            Error::Inference { message, source } => ("Inference failed", message, source),
+            Error::OutputExtraction { message, source } => {
+                ("Output extraction failed", message, source)
+            }
+            Error::Io { path, source } => {
+                return write!(f, "I/O error on '{}': {}", path.display(), source)
+            }
+            Error::PhonemeConversion(error) => return error.fmt(f),
+            Error::QueueFull { limit } => {
+                return write!(
+                    f,
+                    "Session pool queue is full ({} requests already waiting)",
+                    limit
+                )
+            }
+        };
+
+        write!(f, "{}: {}", stage, message)?;
+        if let Some(source) = source {
+            write!(f, ": {}", source)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Config { source, .. }
+            | Error::Tokenizer { source, .. }
+            | Error::G2p { source, .. }
+            | Error::VoiceFormat { source, .. }
+            | Error::UnsupportedModel { source, .. }
+            | Error::Inference { source, .. }
+            | Error::OutputExtraction { source, .. } => source
+                .as_deref()
+                .map(|e| e as &(dyn std::error::Error + 'static)),
+            Error::Io { source, .. } => Some(source),
+            Error::PhonemeConversion(error) => Some(error),
+            Error::QueueFull { .. } => None,
+        }
+    }
+}
+
+impl From<PhonemeConversionError> for Error {
+    fn from(error: PhonemeConversionError) -> Self {
+        Error::PhonemeConversion(error)
+    }
+}
+
+#[cfg(feature = "ort")]
+impl From<ort::OrtError> for Error {
+    fn from(error: ort::OrtError) -> Self {
+        Error::inference("ONNX Runtime error").with_source(error)
+    }
+}
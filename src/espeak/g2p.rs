@@ -1,8 +1,15 @@
+use crate::error::{Error, Result};
+#[cfg(feature = "espeak")]
 use std::ffi::{CStr, CString};
+#[cfg(feature = "espeak")]
 use std::os::raw::{c_char, c_int, c_void};
-use std::sync::Once;
+#[cfg(feature = "espeak")]
+use std::sync::{Mutex, MutexGuard, OnceLock};
+#[cfg(feature = "espeak")]
+use tracing::warn;
 
 // FFI bindings for espeak-ng
+#[cfg(feature = "espeak")]
 #[link(name = "espeak-ng")]
 extern "C" {
     fn espeak_Initialize(
@@ -15,7 +22,7 @@ extern "C" {
     fn espeak_SetVoiceByName(name: *const c_char) -> c_int;
 
     fn espeak_TextToPhonemes(
-        textptr: *const *const c_void,
+        textptr: *mut *const c_void,
         textmode: c_int,
         phonememode: c_int,
     ) -> *const c_char;
@@ -25,46 +32,92 @@ extern "C" {
 }
 
 // Constants
+#[cfg(feature = "espeak")]
 const AUDIO_OUTPUT_RETRIEVAL: c_int = 0x02;
+#[cfg(feature = "espeak")]
 const ESPEAK_PHONEMES_IPA: c_int = 0x02;
+#[cfg(feature = "espeak")]
 const ESPEAK_PHONEMES_SHOW_STRESS: c_int = 0x04;
+#[cfg(feature = "espeak")]
 const ESPEAK_PHONEMES_TIE: c_int = 0x08;
+#[cfg(feature = "espeak")]
 const ESPEAK_CHARS_UTF8: c_int = 1;
 
-static INIT: Once = Once::new();
-static mut INITIALIZED: bool = false;
+/// Voice used by `text_to_ipa` (American English)
+pub const DEFAULT_VOICE: &str = "en-us";
 
+#[cfg(feature = "espeak")]
+static INITIALIZED: OnceLock<bool> = OnceLock::new();
+
+// espeak-ng is not reentrant: it keeps a single active voice and internal
+// buffers shared by every espeak_TextToPhonemes call. All calls into the
+// library hold this lock, which also remembers the active voice to avoid
+// reloading it on every call.
+#[cfg(feature = "espeak")]
+static ESPEAK: Mutex<Option<String>> = Mutex::new(None);
+
+/// Handle to the process-wide espeak-ng instance. It is `Send + Sync`;
+/// concurrent calls are serialized internally.
 pub struct EspeakG2P;
 
 impl EspeakG2P {
-    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+    #[allow(dead_code)]
+    pub fn text_to_ipa(&self, text: &str) -> Result<String> {
+        self.text_to_ipa_with_voice(text, DEFAULT_VOICE)
+    }
+}
+
+#[cfg(not(feature = "espeak"))]
+impl EspeakG2P {
+    pub fn new() -> Result<Self> {
+        Err(Error::config(
+            "kokoroxide was built without the `espeak` feature; configure a CMUdict file with TTSConfig::with_cmudict",
+        ))
+    }
+
+    pub fn text_to_ipa_with_voice(&self, _text: &str, _voice: &str) -> Result<String> {
+        Err(Error::config(
+            "kokoroxide was built without the `espeak` feature",
+        ))
+    }
+}
+
+#[cfg(feature = "espeak")]
+impl EspeakG2P {
+    pub fn new() -> Result<Self> {
         // Initialize espeak-ng only once
-        unsafe {
-            INIT.call_once(|| {
-                let result = espeak_Initialize(AUDIO_OUTPUT_RETRIEVAL, 0, std::ptr::null(), 0);
-                INITIALIZED = result >= 0;
-
-                if INITIALIZED {
-                    // Set voice to American English
-                    let voice_name = CString::new("en-us").unwrap();
-                    let result = espeak_SetVoiceByName(voice_name.as_ptr());
-                    if result != 0 {
-                        eprintln!("Warning: Failed to set voice to en-us, result: {}", result);
-                    }
-                }
-            });
+        let initialized = *INITIALIZED.get_or_init(|| {
+            let mut voice = lock_espeak();
+            let result =
+                unsafe { espeak_Initialize(AUDIO_OUTPUT_RETRIEVAL, 0, std::ptr::null(), 0) };
+            if result < 0 {
+                return false;
+            }
 
-            if !INITIALIZED {
-                return Err("Failed to initialize espeak-ng".into());
+            // Set voice to American English
+            if let Err(e) = select_voice(&mut voice, DEFAULT_VOICE) {
+                warn!(error = %e, "failed to select the default espeak-ng voice");
             }
+            true
+        });
+
+        if !initialized {
+            return Err(Error::g2p("Failed to initialize espeak-ng"));
         }
 
         Ok(EspeakG2P)
     }
 
-    pub fn text_to_ipa(&self, text: &str) -> Result<String, Box<dyn std::error::Error>> {
+    /// Phonemize text with the given espeak-ng voice (e.g. `"es"`).
+    pub fn text_to_ipa_with_voice(&self, text: &str, voice: &str) -> Result<String> {
+        // Held until the whole text is phonemized so another thread can
+        // neither switch the voice nor interleave TextToPhonemes calls
+        let mut current_voice = lock_espeak();
+        select_voice(&mut current_voice, voice)?;
+
         unsafe {
-            let c_text = CString::new(text)?;
+            let c_text = CString::new(text)
+                .map_err(|e| Error::g2p("Text contains a NUL byte").with_source(e))?;
             let mut text_ptr = c_text.as_ptr() as *const c_void;
             let mut all_phonemes = String::new();
 
@@ -102,7 +155,7 @@ impl EspeakG2P {
             }
 
             if all_phonemes.is_empty() {
-                return Err("Failed to get phonemes from espeak-ng".into());
+                return Err(Error::g2p("Failed to get phonemes from espeak-ng"));
             }
 
             Ok(all_phonemes)
@@ -110,6 +163,33 @@ impl EspeakG2P {
     }
 }
 
+#[cfg(feature = "espeak")]
+fn lock_espeak() -> MutexGuard<'static, Option<String>> {
+    // A panic while phonemizing leaves espeak-ng usable; ignore the poison
+    ESPEAK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Switch the active voice. Takes the guard to ensure callers hold the lock.
+#[cfg(feature = "espeak")]
+fn select_voice(current: &mut MutexGuard<'static, Option<String>>, voice: &str) -> Result<()> {
+    if current.as_deref() == Some(voice) {
+        return Ok(());
+    }
+
+    let voice_name = CString::new(voice)
+        .map_err(|e| Error::config("Voice name contains a NUL byte").with_source(e))?;
+    let result = unsafe { espeak_SetVoiceByName(voice_name.as_ptr()) };
+    if result != 0 {
+        return Err(Error::g2p(format!(
+            "Failed to set voice to {}, result: {}",
+            voice, result
+        )));
+    }
+
+    **current = Some(voice.to_string());
+    Ok(())
+}
+
 impl Drop for EspeakG2P {
     fn drop(&mut self) {
         // Note: We don't terminate espeak-ng here because it's a global resource
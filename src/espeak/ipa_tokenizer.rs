@@ -1,32 +1,90 @@
+use super::g2p::DEFAULT_VOICE;
 use super::EspeakG2P;
+use crate::cmudict::CmuDictG2P;
+use crate::error::{Error, Result};
+use crate::phonemes::{PhonemeFallback, PhonemeSubstitution, StressNormalizer};
+use crate::text::{segments, splice_phonemes, HomographResolver, Segment};
 use std::collections::HashMap;
-use std::error::Error;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use tracing::{debug, debug_span, warn};
+
+/// Grapheme-to-phoneme backend used for plain text input
+enum G2PBackend {
+    /// espeak-ng IPA, converted to Misaki
+    Espeak(EspeakG2P),
+    /// CMUdict with letter-to-sound fallback (English only, no C dependency)
+    CmuDict(CmuDictG2P),
+}
+
+/// Token ids for one input, with the intermediate results and timings of
+/// each stage
+pub struct Encoding {
+    pub ids: Vec<i64>,
+    /// Raw espeak-ng IPA, when espeak phonemized the input
+    pub espeak_ipa: Option<String>,
+    /// Misaki phonemes that were tokenized
+    pub phonemes: String,
+    /// Out-of-vocabulary symbols that were replaced or dropped
+    pub substitutions: Vec<PhonemeSubstitution>,
+    /// Tokens cut off to fit the max length
+    pub dropped_tokens: usize,
+    pub g2p_time: Duration,
+    pub tokenize_time: Duration,
+}
+
+/// Output of the G2P stage
+struct Phonemization {
+    espeak_ipa: Option<String>,
+    phonemes: String,
+}
 
 pub struct EspeakIpaTokenizer {
     vocab: HashMap<String, i64>,
+    id_to_token: HashMap<i64, String>,
     bos_id: i64,
     eos_id: i64,
     model_max_length: usize,
-    g2p: EspeakG2P,
+    g2p: G2PBackend,
     max_token_chars: usize,
+    fallback: PhonemeFallback,
+    stress: Option<StressNormalizer>,
+    homographs: Option<HomographResolver>,
 }
 
 impl EspeakIpaTokenizer {
-    pub fn new(vocab: HashMap<String, i64>) -> Result<Self, Box<dyn std::error::Error>> {
-        let bos_id = *vocab.get("$").ok_or("BOS token '$' not found")?;
+    pub fn new(vocab: HashMap<String, i64>) -> Result<Self> {
+        Self::with_backend(vocab, G2PBackend::Espeak(EspeakG2P::new()?))
+    }
+
+    /// Tokenizer that phonemizes text with CMUdict instead of espeak-ng.
+    pub fn with_cmudict(vocab: HashMap<String, i64>, g2p: CmuDictG2P) -> Result<Self> {
+        Self::with_backend(vocab, G2PBackend::CmuDict(g2p))
+    }
+
+    fn with_backend(vocab: HashMap<String, i64>, g2p: G2PBackend) -> Result<Self> {
+        let bos_id = *vocab
+            .get("$")
+            .ok_or_else(|| Error::tokenizer("BOS token '$' not found in vocab"))?;
         let eos_id = bos_id;
 
-        let g2p = EspeakG2P::new()?;
         let max_token_chars = Self::max_token_chars(&vocab);
+        let fallback = PhonemeFallback::new(vocab.keys().map(String::as_str));
+        let id_to_token = vocab
+            .iter()
+            .map(|(token, &id)| (id, token.clone()))
+            .collect();
 
         Ok(Self {
             vocab,
+            id_to_token,
             bos_id,
             eos_id,
             model_max_length: 512,
             g2p,
             max_token_chars,
+            fallback,
+            stress: None,
+            homographs: None,
         })
     }
 
@@ -35,6 +93,30 @@ impl EspeakIpaTokenizer {
         self
     }
 
+    /// Longest token sequence (with BOS/EOS) `encode` keeps by default
+    pub fn model_max_length(&self) -> usize {
+        self.model_max_length
+    }
+
+    /// Normalize stress in English output to Misaki conventions, leaving the
+    /// given function words unstressed.
+    pub fn with_stress_normalization(mut self, function_words: &[&str]) -> Self {
+        let mut stress = StressNormalizer::new();
+        for word in function_words {
+            stress.add_function_word(word);
+        }
+
+        self.stress = Some(stress);
+        self
+    }
+
+    /// Resolve homographs and pronunciation overrides in English text before
+    /// phonemization; the remaining text still goes through the G2P backend.
+    pub fn with_homographs(mut self, resolver: HomographResolver) -> Self {
+        self.homographs = Some(resolver);
+        self
+    }
+
     /// Convert espeak IPA to Misaki phonemes to match kokoro Python output
     fn espeak_ipa_to_misaki(&self, ipa: &str) -> String {
         // First, replace the Unicode tie bar (U+0361) with caret (^) to match Python
@@ -71,25 +153,7 @@ impl EspeakIpaTokenizer {
             result = result.replace(old, new);
         }
 
-        // Handle syllabic consonants: (\S)̩ -> ᵊ\1
-        // This is regex: r'(\S)\u0329' -> r'ᵊ\1' in Python
-        let mut chars: Vec<char> = result.chars().collect();
-        let mut i = 0;
-        while i < chars.len() {
-            if i + 1 < chars.len() && chars[i + 1] == '\u{0329}' {
-                // Found syllabic marker after a character
-                let consonant = chars[i];
-                chars[i] = 'ᵊ';
-                chars[i + 1] = consonant;
-                i += 2;
-            } else {
-                i += 1;
-            }
-        }
-        result = chars.into_iter().collect();
-
-        // Remove any remaining syllabic markers (chr(809) in Python)
-        result = result.replace('\u{0329}', "");
+        result = move_syllabic_marks(&result);
 
         // Apply American English specific transformations (british = false)
         result = result.replace("o^ʊ", "O");
@@ -104,25 +168,208 @@ impl EspeakIpaTokenizer {
         result
     }
 
-    fn text_to_ipa(&self, text: &str) -> Result<String, Box<dyn Error>> {
-        let ipa = self.g2p.text_to_ipa(text)?;
+    /// Convert espeak IPA from non-English voices, following Misaki's generic
+    /// espeak mapping (diphthongs and affricates only)
+    fn espeak_ipa_to_misaki_generic(&self, ipa: &str) -> String {
+        let mut result = ipa.replace('\u{0361}', "^");
 
-        let misaki_phonemes = self.espeak_ipa_to_misaki(&ipa);
+        let from_espeaks = [
+            ("a^ɪ", "I"),
+            ("a^ʊ", "W"),
+            ("d^z", "ʣ"),
+            ("d^ʒ", "ʤ"),
+            ("e^ɪ", "A"),
+            ("o^ʊ", "O"),
+            ("ə^ʊ", "Q"),
+            ("s^s", "S"),
+            ("t^s", "ʦ"),
+            ("t^ʃ", "ʧ"),
+            ("ɔ^ɪ", "Y"),
+        ];
+
+        for (old, new) in from_espeaks {
+            result = result.replace(old, new);
+        }
+
+        result = move_syllabic_marks(&result);
+        result.replace("^", "")
+    }
+
+    /// Misaki phonemes for text, exactly as `encode_with_voice` tokenizes them.
+    pub fn phonemize(&self, text: &str, voice: &str) -> Result<String> {
+        Ok(self.text_to_ipa(text, voice)?.phonemes)
+    }
+
+    /// Phoneme string for token ids. BOS/EOS and ids missing from the vocab
+    /// are skipped.
+    pub fn decode(&self, ids: &[i64]) -> String {
+        ids.iter()
+            .filter(|&&id| id != self.bos_id && id != self.eos_id)
+            .filter_map(|id| self.id_to_token.get(id))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Token id used to pad batched input (the BOS/EOS symbol)
+    pub fn pad_id(&self) -> i64 {
+        self.eos_id
+    }
 
+    fn text_to_ipa(&self, text: &str, voice: &str) -> Result<Phonemization> {
+        let Phonemization {
+            espeak_ipa,
+            phonemes: misaki_phonemes,
+        } = match &self.homographs {
+            Some(resolver) if voice.starts_with("en") => {
+                self.text_to_misaki_resolved(resolver, text, voice)?
+            }
+            _ => self.text_to_misaki(text, voice)?,
+        };
+
+        let misaki_phonemes = match &self.stress {
+            Some(stress) if voice.starts_with("en") => {
+                let normalized = stress.normalize(text, &misaki_phonemes);
+                debug!(phonemes = %normalized, "stress normalized");
+                if std::env::var("DEBUG_PHONEMES").is_ok() {
+                    println!("Stress normalized: '{}'", normalized);
+                }
+                normalized
+            }
+            _ => misaki_phonemes,
+        };
+
+        Ok(Phonemization {
+            espeak_ipa,
+            phonemes: misaki_phonemes,
+        })
+    }
+
+    /// Phonemize the whole text, so the G2P backend sees each sentence in
+    /// context, then splice in the phonemes of resolved homographs and
+    /// pronunciation overrides. If the output can't be matched up with the
+    /// input words, the text around resolved words is phonemized run by run.
+    fn text_to_misaki_resolved(
+        &self,
+        resolver: &HomographResolver,
+        text: &str,
+        voice: &str,
+    ) -> Result<Phonemization> {
+        let resolved = resolver.resolve_words(text);
+        let phonemized = self.text_to_misaki(text, voice)?;
+        if resolved.is_empty() {
+            return Ok(phonemized);
+        }
+        if let Some(phonemes) = splice_phonemes(text, &phonemized.phonemes, &resolved) {
+            return Ok(Phonemization {
+                espeak_ipa: phonemized.espeak_ipa,
+                phonemes,
+            });
+        }
+        debug!(
+            resolved = resolved.len(),
+            "phonemes don't align with the input words, phonemizing around resolved words"
+        );
+
+        let mut ipa_parts = Vec::new();
+        let mut parts: Vec<String> = Vec::new();
+        for segment in segments(text, &resolved) {
+            match segment {
+                // Nothing to phonemize, but the punctuation still marks phrasing
+                Segment::Text(run) if !run.chars().any(char::is_alphanumeric) => {
+                    let punctuation: String = run
+                        .chars()
+                        .filter(|c| !c.is_whitespace() && self.vocab.contains_key(&c.to_string()))
+                        .collect();
+                    match parts.last_mut() {
+                        Some(last) if !run.starts_with(char::is_whitespace) => {
+                            last.push_str(&punctuation)
+                        }
+                        _ if !punctuation.is_empty() => parts.push(punctuation),
+                        _ => {}
+                    }
+                }
+                Segment::Text(run) => {
+                    let phonemized = self.text_to_misaki(run, voice)?;
+                    ipa_parts.extend(phonemized.espeak_ipa);
+                    parts.push(phonemized.phonemes);
+                }
+                Segment::Phonemes(phonemes) => parts.push(phonemes),
+            }
+        }
+        Ok(Phonemization {
+            espeak_ipa: (!ipa_parts.is_empty()).then(|| ipa_parts.join(" ")),
+            phonemes: parts.join(" "),
+        })
+    }
+
+    /// Phonemize text with the G2P backend and convert the result to Misaki
+    fn text_to_misaki(&self, text: &str, voice: &str) -> Result<Phonemization> {
+        let g2p = match &self.g2p {
+            G2PBackend::Espeak(g2p) => g2p,
+            G2PBackend::CmuDict(g2p) => {
+                if !voice.starts_with("en") {
+                    return Err(Error::config(format!(
+                        "The CMUdict backend only supports English, cannot phonemize with voice '{}'",
+                        voice
+                    )));
+                }
+
+                let misaki_phonemes = g2p.phonemize(text)?;
+                debug!(
+                    backend = "cmudict",
+                    text_len = text.len(),
+                    phonemes = %misaki_phonemes,
+                    "phonemized"
+                );
+                if std::env::var("DEBUG_PHONEMES").is_ok() {
+                    println!("Input text: '{}'", text);
+                    println!("Misaki phonemes (CMUdict): '{}'", misaki_phonemes);
+                }
+                return Ok(Phonemization {
+                    espeak_ipa: None,
+                    phonemes: misaki_phonemes,
+                });
+            }
+        };
+
+        let ipa = g2p.text_to_ipa_with_voice(text, voice)?;
+
+        let misaki_phonemes = if voice.starts_with("en") {
+            self.espeak_ipa_to_misaki(&ipa)
+        } else {
+            self.espeak_ipa_to_misaki_generic(&ipa)
+        };
+
+        debug!(
+            backend = "espeak",
+            voice,
+            text_len = text.len(),
+            ipa = %ipa,
+            phonemes = %misaki_phonemes,
+            "phonemized"
+        );
         if std::env::var("DEBUG_PHONEMES").is_ok() {
             println!("Input text: '{}'", text);
             println!("Espeak IPA: '{}'", ipa);
             println!("Misaki phonemes: '{}'", misaki_phonemes);
         }
-        Ok(misaki_phonemes)
+        Ok(Phonemization {
+            espeak_ipa: Some(ipa),
+            phonemes: misaki_phonemes,
+        })
     }
 
     fn max_token_chars(vocab: &HashMap<String, i64>) -> usize {
         vocab.keys().map(|k| k.chars().count()).max().unwrap_or(1)
     }
 
-    pub fn tokenize_longest(&self, ipa: &str) -> Vec<i64> {
+    /// Greedy longest-match tokenization. A symbol missing from the vocab is
+    /// replaced with the phonetically closest vocab symbol, or dropped when it
+    /// has none (diacritics, unknown letters); each case is reported with its
+    /// character position. Whitespace missing from the vocab is skipped.
+    pub fn tokenize_with_substitutions(&self, ipa: &str) -> (Vec<i64>, Vec<PhonemeSubstitution>) {
         let mut ids = Vec::with_capacity(ipa.len());
+        let mut substitutions = Vec::new();
         let chars: Vec<char> = ipa.chars().collect();
         let mut i = 0;
         let max_len = self.max_token_chars;
@@ -143,108 +390,162 @@ impl EspeakIpaTokenizer {
             }
 
             if !matched {
-                // Optional: ignore pure whitespace if not in vocab
                 if !chars[i].is_whitespace() {
-                    eprintln!("Warning: unknown token {:?}", chars[i]);
+                    let replacement = self.fallback.closest(chars[i]);
+                    debug!(
+                        position = i,
+                        original = %chars[i],
+                        replacement = ?replacement,
+                        "out-of-vocabulary phoneme"
+                    );
+                    if let Some(&id) = replacement.and_then(|c| self.vocab.get(&c.to_string())) {
+                        ids.push(id);
+                    }
+                    substitutions.push(PhonemeSubstitution {
+                        position: i,
+                        original: chars[i],
+                        replacement,
+                    });
                 }
                 i += 1;
             }
         }
-        ids
+        (ids, substitutions)
     }
 
-    pub fn encode_phonemes(
-        &self,
-        phonemes: &str,
-        max_length: Option<usize>,
-    ) -> Result<Vec<i64>, Box<dyn std::error::Error>> {
-        let start_time = Instant::now();
-        let max_len = max_length.unwrap_or(self.model_max_length);
-
-        // BOS
-        let mut tokens = Vec::with_capacity(phonemes.len() + 2);
+    /// Wrap tokens in BOS/EOS, truncating to `max_len` while keeping both.
+    /// Returns the tokens and how many were dropped.
+    fn add_special_tokens(&self, inner: Vec<i64>, max_len: usize) -> (Vec<i64>, usize) {
+        let keep_inner = inner.len().min(max_len.saturating_sub(2));
+        let mut tokens = Vec::with_capacity(keep_inner + 2);
         tokens.push(self.bos_id);
-
-        // Tokenize the phonemes directly
-        let mut inner = self.tokenize_longest(phonemes);
-        tokens.append(&mut inner);
-
-        // EOS
+        tokens.extend_from_slice(&inner[..keep_inner]);
         tokens.push(self.eos_id);
+        (tokens, inner.len() - keep_inner)
+    }
 
-        // Truncate if needed (keep BOS/EOS)
-        if tokens.len() > max_len {
-            let keep_inner = max_len.saturating_sub(2);
-            let mut truncated = Vec::with_capacity(max_len);
-            truncated.push(self.bos_id);
-            truncated.extend_from_slice(&tokens[1..1 + keep_inner]);
-            truncated.push(self.eos_id);
-            if std::env::var("DEBUG_TIMING").is_ok() {
-                println!(
-                    "Direct phoneme tokenization time: {:?}",
-                    start_time.elapsed()
-                );
-            }
-            return Ok(truncated);
-        }
+    pub fn encode_phonemes(&self, phonemes: &str, max_length: Option<usize>) -> Result<Encoding> {
+        let _span =
+            debug_span!("encode_phonemes", phoneme_len = phonemes.chars().count()).entered();
+        let max_len = max_length.unwrap_or(self.model_max_length);
+
+        // Tokenize the phonemes directly
+        let encoding = self.tokenize_phonemized(
+            Phonemization {
+                espeak_ipa: None,
+                phonemes: phonemes.to_string(),
+            },
+            Duration::ZERO,
+            max_len,
+        );
 
         if std::env::var("DEBUG_TIMING").is_ok() {
             println!(
                 "Direct phoneme tokenization time: {:?}",
-                start_time.elapsed()
+                encoding.tokenize_time
             );
         }
-        if std::env::var("DEBUG_TOKENS").is_ok() {
-            println!("tokens = {:?}", tokens);
-        }
-        Ok(tokens)
+        Ok(encoding)
+    }
+
+    #[allow(dead_code)]
+    pub fn encode(&self, text: &str, max_length: Option<usize>) -> Result<Vec<i64>> {
+        Ok(self.encode_with_voice(text, DEFAULT_VOICE, max_length)?.ids)
     }
 
-    pub fn encode(
+    /// Encode text phonemized with a specific espeak-ng voice (e.g. `"es"`).
+    /// English voices use the American Misaki mapping, others the generic one.
+    pub fn encode_with_voice(
         &self,
         text: &str,
+        voice: &str,
         max_length: Option<usize>,
-    ) -> Result<Vec<i64>, Box<dyn std::error::Error>> {
+    ) -> Result<Encoding> {
+        let _span = debug_span!("encode", text_len = text.len(), voice).entered();
         let start_time = Instant::now();
         let max_len = max_length.unwrap_or(self.model_max_length);
 
-        // BOS
-        let mut tokens = Vec::with_capacity(text.len() + 2);
-        tokens.push(self.bos_id);
-
         let ipa_start = Instant::now();
-        let ipa_text = self.text_to_ipa(text)?;
+        let phonemized = debug_span!("g2p").in_scope(|| self.text_to_ipa(text, voice))?;
+        let g2p_time = ipa_start.elapsed();
+        debug!(
+            phoneme_count = phonemized.phonemes.chars().count(),
+            elapsed_ms = g2p_time.as_secs_f64() * 1000.0,
+            "g2p finished"
+        );
         if std::env::var("DEBUG_TIMING").is_ok() {
             println!(
                 "Phoneme tokenization (espeak IPA conversion) took: {:?}",
-                ipa_start.elapsed()
+                g2p_time
             );
         }
 
-        let mut inner = self.tokenize_longest(&ipa_text);
-        tokens.append(&mut inner);
-
-        // EOS
-        tokens.push(self.eos_id);
-
-        if tokens.len() > max_len {
-            let keep_inner = max_len.saturating_sub(2);
-            let mut truncated = Vec::with_capacity(max_len);
-            truncated.push(self.bos_id);
-            truncated.extend_from_slice(&tokens[1..1 + keep_inner]);
-            truncated.push(self.eos_id);
-            if std::env::var("DEBUG_TIMING").is_ok() {
-                println!("Total tokenization time: {:?}", start_time.elapsed());
-            }
-            return Ok(truncated);
-        }
+        let encoding = self.tokenize_phonemized(phonemized, g2p_time, max_len);
 
         if std::env::var("DEBUG_TIMING").is_ok() {
             println!("Total tokenization time: {:?}", start_time.elapsed());
         }
+        Ok(encoding)
+    }
+
+    /// Tokenize G2P output, add BOS/EOS and truncate to `max_len`
+    fn tokenize_phonemized(
+        &self,
+        phonemized: Phonemization,
+        g2p_time: Duration,
+        max_len: usize,
+    ) -> Encoding {
+        let _span = debug_span!("tokenize").entered();
+        let tokenize_start = Instant::now();
+
+        let (inner, substitutions) = self.tokenize_with_substitutions(&phonemized.phonemes);
+        let (tokens, dropped_tokens) = self.add_special_tokens(inner, max_len);
+        let tokenize_time = tokenize_start.elapsed();
+
+        if dropped_tokens > 0 {
+            warn!(dropped_tokens, max_length = max_len, "input truncated");
+        }
+        debug!(
+            token_count = tokens.len(),
+            dropped_tokens,
+            substitutions = substitutions.len(),
+            elapsed_ms = tokenize_time.as_secs_f64() * 1000.0,
+            "tokenized"
+        );
         if std::env::var("DEBUG_TOKENS").is_ok() {
             println!("tokens = {:?}", tokens);
         }
-        Ok(tokens)
+
+        Encoding {
+            ids: tokens,
+            espeak_ipa: phonemized.espeak_ipa,
+            phonemes: phonemized.phonemes,
+            substitutions,
+            dropped_tokens,
+            g2p_time,
+            tokenize_time,
+        }
+    }
+}
+
+/// Handle syllabic consonants: (\S)̩ -> ᵊ\1
+/// This is regex: r'(\S)\u0329' -> r'ᵊ\1' in Python
+fn move_syllabic_marks(ipa: &str) -> String {
+    let mut chars: Vec<char> = ipa.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        if i + 1 < chars.len() && chars[i + 1] == '\u{0329}' {
+            // Found syllabic marker after a character
+            let consonant = chars[i];
+            chars[i] = 'ᵊ';
+            chars[i + 1] = consonant;
+            i += 2;
+        } else {
+            i += 1;
+        }
     }
+    let result: String = chars.into_iter().collect();
+
+    // Remove any remaining syllabic markers (chr(809) in Python)
+    result.replace('\u{0329}', "")
 }
@@ -2,4 +2,4 @@ pub mod g2p;
 pub mod ipa_tokenizer;
 
 pub use g2p::EspeakG2P;
-pub use ipa_tokenizer::EspeakIpaTokenizer;
+pub use ipa_tokenizer::{Encoding, EspeakIpaTokenizer};
@@ -3,6 +3,9 @@ use crate::playback::play_wav_file;
 use std::io::{self, Write};
 use std::time::Instant;
 
+/// Optimized graphs are saved here so only the first start pays for optimization
+const MODEL_CACHE_DIR: &str = "models/kokoro/cache";
+
 pub struct InteractiveTTS {
     tts: KokoroTTS,
     voice_style: crate::kokoro::VoiceStyle,
@@ -14,7 +17,7 @@ impl InteractiveTTS {
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
         let tts_config =
             TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
-                .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+                .with_optimized_model_cache(MODEL_CACHE_DIR)
                 .with_max_tokens_length(512)
                 .with_sample_rate(24000);
 
@@ -66,11 +69,17 @@ impl InteractiveTTS {
         self.output_counter += 1;
         let filename = "interactive_tts.wav";
         let audio = self.tts.generate_speech(text, &self.voice_style, 1.0)?;
-        audio.save_to_wav(&filename)?;
+        audio.save_to_wav(filename)?;
         let generation_time = start_time.elapsed();
 
-        play_wav_file(&filename)?;
+        play_wav_file(filename)?;
 
+        tracing::info!(
+            elapsed_ms = generation_time.as_secs_f64() * 1000.0,
+            audio_seconds = audio.duration_seconds,
+            rtf = audio.real_time_factor(generation_time),
+            "generated"
+        );
         if std::env::var("DEBUG_TIMING").is_ok() {
             println!(
                 "Generated in {:.2}s ({:.1}x realtime)",
@@ -96,7 +105,7 @@ pub fn run_interactive_tts_with_options(
     println!("Initializing Interactive TTS with custom options...");
 
     let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
-        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_optimized_model_cache(MODEL_CACHE_DIR)
         .with_max_tokens_length(512)
         .with_sample_rate(24000);
 
@@ -146,6 +155,12 @@ pub fn run_interactive_tts_with_options(
 
                 let generation_time = start_time.elapsed();
 
+                tracing::info!(
+                    elapsed_ms = generation_time.as_secs_f64() * 1000.0,
+                    audio_seconds = audio.duration_seconds,
+                    rtf = audio.real_time_factor(generation_time),
+                    "generated"
+                );
                 if std::env::var("DEBUG_TIMING").is_ok() {
                     println!(
                         "Generation completed in: {:.2}s",
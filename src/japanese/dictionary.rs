@@ -0,0 +1,117 @@
+use super::kana::{count_morae, is_small_kana, to_katakana, ACCENT_MARK};
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Kanji (or any other surface form) to kana reading lookup used before
+/// mora conversion.
+///
+/// The file format is UTF-8 text with one tab-separated entry per line,
+/// `surface<TAB>reading[<TAB>accent]`, e.g. `箸<TAB>はし<TAB>1` and
+/// `橋<TAB>はし<TAB>2`. Lines starting with `#` are comments.
+///
+/// `accent` is the mora index of the accent nucleus (the last high mora before
+/// the pitch falls), with `0` meaning the word is flat (heiban).
+#[derive(Clone, Default)]
+pub struct ReadingDictionary {
+    entries: HashMap<String, String>,
+    max_surface_chars: usize,
+}
+
+impl ReadingDictionary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut dictionary = Self::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 2 {
+                return Err(Error::g2p(format!(
+                    "Invalid reading dictionary entry on line {}: expected 'surface<TAB>reading'",
+                    line_number + 1
+                )));
+            }
+
+            let accent = match fields.get(2).map(|f| f.trim()) {
+                Some(value) if !value.is_empty() => Some(value.parse::<usize>().map_err(|e| {
+                    Error::g2p(format!(
+                        "Invalid accent '{}' on line {} of reading dictionary",
+                        value,
+                        line_number + 1
+                    ))
+                    .with_source(e)
+                })?),
+                _ => None,
+            };
+
+            dictionary.insert(fields[0].trim(), fields[1].trim(), accent)?;
+        }
+
+        Ok(dictionary)
+    }
+
+    /// Add or replace an entry. The reading may be hiragana or katakana.
+    pub fn insert(&mut self, surface: &str, reading: &str, accent: Option<usize>) -> Result<()> {
+        if surface.is_empty() {
+            return Err(Error::g2p(
+                "Reading dictionary surface form must not be empty",
+            ));
+        }
+
+        let mut reading = to_katakana(reading);
+        if let Some(nucleus) = accent.filter(|&n| n > 0) {
+            reading = insert_accent_mark(&reading, nucleus).ok_or_else(|| {
+                Error::g2p(format!(
+                    "Accent {} is past the end of reading '{}' for '{}'",
+                    nucleus, reading, surface
+                ))
+            })?;
+        }
+
+        self.max_surface_chars = self.max_surface_chars.max(surface.chars().count());
+        self.entries.insert(surface.to_string(), reading);
+        Ok(())
+    }
+
+    /// Find the longest entry that is a prefix of `chars`, returning the number
+    /// of characters consumed and the katakana reading.
+    pub fn longest_match(&self, chars: &[char]) -> Option<(usize, &str)> {
+        let limit = self.max_surface_chars.min(chars.len());
+        for len in (1..=limit).rev() {
+            let candidate: String = chars[..len].iter().collect();
+            if let Some(reading) = self.entries.get(&candidate) {
+                return Some((len, reading));
+            }
+        }
+        None
+    }
+}
+
+/// Place the accent mark after the `nucleus`-th mora of a katakana reading.
+fn insert_accent_mark(reading: &str, nucleus: usize) -> Option<String> {
+    let chars: Vec<char> = reading.chars().collect();
+    for end in 1..=chars.len() {
+        let prefix: String = chars[..end].iter().collect();
+        // Keep small kana attached to the mora they modify
+        let next_is_small = chars.get(end).map(|&c| is_small_kana(c)).unwrap_or(false);
+        if count_morae(&prefix) == nucleus && !next_is_small {
+            let rest: String = chars[end..].iter().collect();
+            return Some(format!("{}{}{}", prefix, ACCENT_MARK, rest));
+        }
+    }
+    None
+}
@@ -0,0 +1,327 @@
+use super::ReadingDictionary;
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+
+/// Marks the accent nucleus in kana input: the pitch falls after the mora
+/// that precedes it (e.g. `ハ'シ` for 箸, `ハシ'` for 橋).
+pub const ACCENT_MARK: char = '\'';
+/// Alternative accent mark accepted in input (U+A71C, as used in accent dictionaries)
+const ALT_ACCENT_MARK: char = 'ꜜ';
+/// Misaki pitch-fall symbol emitted after the accent nucleus
+const PITCH_FALL: &str = "↓";
+/// Misaki symbol for the first half of a geminate consonant (ッ)
+const GEMINATE: &str = "ʔ";
+/// Misaki length mark for long vowels (ー and repeated vowels)
+const LONG_VOWEL: &str = "ː";
+
+/// Katakana morae (including two-character combinations with small kana)
+/// mapped to Misaki Japanese phonemes.
+#[rustfmt::skip]
+const MORA_TO_MISAKI: &[(&str, &str)] = &[
+    // Vowels
+    ("ア", "a"), ("イ", "i"), ("ウ", "u"), ("エ", "e"), ("オ", "o"),
+    ("ァ", "a"), ("ィ", "i"), ("ゥ", "u"), ("ェ", "e"), ("ォ", "o"),
+    // K / G
+    ("カ", "ka"), ("キ", "ki"), ("ク", "ku"), ("ケ", "ke"), ("コ", "ko"),
+    ("ヵ", "ka"), ("ヶ", "ke"),
+    ("ガ", "ɡa"), ("ギ", "ɡi"), ("グ", "ɡu"), ("ゲ", "ɡe"), ("ゴ", "ɡo"),
+    ("キャ", "kʲa"), ("キュ", "kʲu"), ("キョ", "kʲo"),
+    ("ギャ", "ɡʲa"), ("ギュ", "ɡʲu"), ("ギョ", "ɡʲo"),
+    // S / Z
+    ("サ", "sa"), ("シ", "ɕi"), ("ス", "su"), ("セ", "se"), ("ソ", "so"),
+    ("ザ", "za"), ("ジ", "ʥi"), ("ズ", "zu"), ("ゼ", "ze"), ("ゾ", "zo"),
+    ("シャ", "ɕa"), ("シュ", "ɕu"), ("ショ", "ɕo"), ("シェ", "ɕe"),
+    ("ジャ", "ʥa"), ("ジュ", "ʥu"), ("ジョ", "ʥo"), ("ジェ", "ʥe"),
+    ("スィ", "si"), ("ズィ", "zi"),
+    // T / D
+    ("タ", "ta"), ("チ", "ʨi"), ("ツ", "ʦu"), ("テ", "te"), ("ト", "to"),
+    ("ダ", "da"), ("ヂ", "ʥi"), ("ヅ", "zu"), ("デ", "de"), ("ド", "do"),
+    ("チャ", "ʨa"), ("チュ", "ʨu"), ("チョ", "ʨo"), ("チェ", "ʨe"),
+    ("ヂャ", "ʥa"), ("ヂュ", "ʥu"), ("ヂョ", "ʥo"),
+    ("ツァ", "ʦa"), ("ツィ", "ʦi"), ("ツェ", "ʦe"), ("ツォ", "ʦo"),
+    ("ティ", "ti"), ("トゥ", "tu"), ("テュ", "tʲu"),
+    ("ディ", "di"), ("ドゥ", "du"), ("デュ", "dʲu"),
+    // N
+    ("ナ", "na"), ("ニ", "ɲi"), ("ヌ", "nu"), ("ネ", "ne"), ("ノ", "no"),
+    ("ニャ", "ɲa"), ("ニュ", "ɲu"), ("ニョ", "ɲo"),
+    // H / B / P
+    ("ハ", "ha"), ("ヒ", "çi"), ("フ", "ɸu"), ("ヘ", "he"), ("ホ", "ho"),
+    ("バ", "ba"), ("ビ", "bi"), ("ブ", "bu"), ("ベ", "be"), ("ボ", "bo"),
+    ("パ", "pa"), ("ピ", "pi"), ("プ", "pu"), ("ペ", "pe"), ("ポ", "po"),
+    ("ヒャ", "ça"), ("ヒュ", "çu"), ("ヒョ", "ço"),
+    ("ビャ", "bʲa"), ("ビュ", "bʲu"), ("ビョ", "bʲo"),
+    ("ピャ", "pʲa"), ("ピュ", "pʲu"), ("ピョ", "pʲo"),
+    ("ファ", "ɸa"), ("フィ", "ɸi"), ("フェ", "ɸe"), ("フォ", "ɸo"), ("フュ", "ɸʲu"),
+    // M
+    ("マ", "ma"), ("ミ", "mi"), ("ム", "mu"), ("メ", "me"), ("モ", "mo"),
+    ("ミャ", "mʲa"), ("ミュ", "mʲu"), ("ミョ", "mʲo"),
+    // Y
+    ("ヤ", "ja"), ("ユ", "ju"), ("ヨ", "jo"),
+    ("ャ", "ja"), ("ュ", "ju"), ("ョ", "jo"),
+    ("イェ", "je"),
+    // R
+    ("ラ", "ɾa"), ("リ", "ɾi"), ("ル", "ɾu"), ("レ", "ɾe"), ("ロ", "ɾo"),
+    ("リャ", "ɾʲa"), ("リュ", "ɾʲu"), ("リョ", "ɾʲo"),
+    // W
+    ("ワ", "wa"), ("ヰ", "i"), ("ヱ", "e"), ("ヲ", "o"), ("ヮ", "wa"),
+    ("ウィ", "wi"), ("ウェ", "we"), ("ウォ", "wo"),
+    // V
+    ("ヴ", "vu"), ("ヴァ", "va"), ("ヴィ", "vi"), ("ヴェ", "ve"), ("ヴォ", "vo"),
+];
+
+/// Japanese punctuation mapped to the punctuation symbols in the Kokoro vocab
+#[rustfmt::skip]
+const PUNCTUATION: &[(char, &str)] = &[
+    ('。', "."), ('．', "."), ('、', ","), ('，', ","),
+    ('？', "?"), ('！', "!"), ('：', ":"), ('；', ";"),
+    ('「', "“"), ('」', "”"), ('『', "“"), ('』', "”"),
+    ('（', "("), ('）', ")"), ('…', "…"), ('―', "—"),
+    ('・', " "), ('　', " "),
+    ('.', "."), (',', ","), ('?', "?"), ('!', "!"), (':', ":"), (';', ";"),
+    ('(', "("), (')', ")"), ('"', "\""),
+];
+
+enum Unit {
+    Mora(&'static str),
+    /// A plain vowel kana (ア, イ, ウ, エ, オ), which lengthens a preceding identical vowel
+    Vowel(&'static str),
+    Geminate,
+    MoraicNasal,
+    LongVowel,
+    Accent,
+    Text(&'static str),
+}
+
+/// Converts hiragana/katakana text (and kanji covered by a reading dictionary)
+/// to Misaki Japanese phonemes for Kokoro's `jf_*`/`jm_*` voices.
+pub struct JapanesePhonemizer {
+    morae: HashMap<&'static str, &'static str>,
+    punctuation: HashMap<char, &'static str>,
+    dictionary: ReadingDictionary,
+}
+
+impl JapanesePhonemizer {
+    pub fn new() -> Self {
+        JapanesePhonemizer {
+            morae: MORA_TO_MISAKI.iter().cloned().collect(),
+            punctuation: PUNCTUATION.iter().cloned().collect(),
+            dictionary: ReadingDictionary::new(),
+        }
+    }
+
+    pub fn with_dictionary(mut self, dictionary: ReadingDictionary) -> Self {
+        self.dictionary = dictionary;
+        self
+    }
+
+    pub fn phonemize(&self, text: &str) -> Result<String> {
+        let kana = self.apply_readings(text)?;
+        let units = self.parse_units(&kana)?;
+
+        let phonemes = render_units(&units);
+        tracing::debug!(
+            text_len = text.len(),
+            kana = %kana,
+            phonemes = %phonemes,
+            "phonemized Japanese"
+        );
+        if std::env::var("DEBUG_PHONEMES").is_ok() {
+            println!("Input text: '{}'", text);
+            println!("Kana reading: '{}'", kana);
+            println!("Misaki phonemes: '{}'", phonemes);
+        }
+        Ok(phonemes)
+    }
+
+    /// Replace dictionary surfaces with their readings and fold hiragana to katakana.
+    fn apply_readings(&self, text: &str) -> Result<String> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut result = String::with_capacity(text.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            if let Some((consumed, reading)) = self.dictionary.longest_match(&chars[i..]) {
+                result.push_str(reading);
+                i += consumed;
+                continue;
+            }
+
+            let c = chars[i];
+            if is_kanji(c) {
+                return Err(Error::g2p(format!(
+                    "No reading for '{}'; add it to the Japanese reading dictionary",
+                    c
+                )));
+            }
+            result.push(hiragana_to_katakana(c));
+            i += 1;
+        }
+
+        Ok(result)
+    }
+
+    fn parse_units(&self, kana: &str) -> Result<Vec<Unit>> {
+        let chars: Vec<char> = kana.chars().collect();
+        let mut units = Vec::with_capacity(chars.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+
+            // Try two-character morae (e.g. キャ, ファ) before single kana
+            if i + 1 < chars.len() && is_small_kana(chars[i + 1]) {
+                let pair: String = chars[i..i + 2].iter().collect();
+                if let Some(&phonemes) = self.morae.get(pair.as_str()) {
+                    units.push(Unit::Mora(phonemes));
+                    i += 2;
+                    continue;
+                }
+            }
+
+            let unit = match c {
+                'ッ' => Unit::Geminate,
+                'ン' => Unit::MoraicNasal,
+                'ー' | '〜' | '～' => Unit::LongVowel,
+                ACCENT_MARK | ALT_ACCENT_MARK => Unit::Accent,
+                c if c.is_whitespace() => Unit::Text(" "),
+                _ => {
+                    let single = c.to_string();
+                    if let Some(&phonemes) = self.morae.get(single.as_str()) {
+                        if matches!(c, 'ア' | 'イ' | 'ウ' | 'エ' | 'オ') {
+                            Unit::Vowel(phonemes)
+                        } else {
+                            Unit::Mora(phonemes)
+                        }
+                    } else if let Some(&symbol) = self.punctuation.get(&c) {
+                        Unit::Text(symbol)
+                    } else {
+                        return Err(Error::g2p(format!(
+                            "Unsupported character '{}' in Japanese text",
+                            c
+                        )));
+                    }
+                }
+            };
+            units.push(unit);
+            i += 1;
+        }
+
+        Ok(units)
+    }
+}
+
+impl Default for JapanesePhonemizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn render_units(units: &[Unit]) -> String {
+    let mut result = String::new();
+    let mut last_vowel: Option<char> = None;
+
+    for (index, unit) in units.iter().enumerate() {
+        match unit {
+            Unit::Mora(phonemes) => {
+                result.push_str(phonemes);
+                last_vowel = phonemes.chars().last();
+            }
+            Unit::Vowel(phonemes) => {
+                // Repeating the previous mora's vowel makes it long (おおきい)
+                if last_vowel == phonemes.chars().next() {
+                    result.push_str(LONG_VOWEL);
+                } else {
+                    result.push_str(phonemes);
+                }
+                last_vowel = phonemes.chars().last();
+            }
+            Unit::Geminate => {
+                result.push_str(GEMINATE);
+                last_vowel = None;
+            }
+            Unit::MoraicNasal => {
+                result.push_str(moraic_nasal(next_mora(&units[index + 1..])));
+                last_vowel = None;
+            }
+            Unit::LongVowel => {
+                if last_vowel.is_some() {
+                    result.push_str(LONG_VOWEL);
+                }
+            }
+            Unit::Accent => result.push_str(PITCH_FALL),
+            Unit::Text(symbol) => {
+                result.push_str(symbol);
+                last_vowel = None;
+            }
+        }
+    }
+
+    result.trim().to_string()
+}
+
+/// The moraic nasal ン assimilates to the place of articulation of the next mora.
+fn moraic_nasal(next: Option<&str>) -> &'static str {
+    match next.and_then(|phonemes| phonemes.chars().next()) {
+        Some('m' | 'p' | 'b') => "m",
+        Some('t' | 'd' | 'n' | 'ɲ' | 'ɾ' | 'ʦ' | 'ʨ' | 'ʥ' | 'z') => "n",
+        Some('k' | 'ɡ') => "ŋ",
+        _ => "ɴ",
+    }
+}
+
+fn next_mora(units: &[Unit]) -> Option<&str> {
+    for unit in units {
+        match unit {
+            Unit::Mora(phonemes) | Unit::Vowel(phonemes) => return Some(phonemes),
+            Unit::Accent => continue,
+            _ => return None,
+        }
+    }
+    None
+}
+
+pub fn is_small_kana(c: char) -> bool {
+    matches!(
+        c,
+        'ァ' | 'ィ'
+            | 'ゥ'
+            | 'ェ'
+            | 'ォ'
+            | 'ャ'
+            | 'ュ'
+            | 'ョ'
+            | 'ヮ'
+            | 'ぁ'
+            | 'ぃ'
+            | 'ぅ'
+            | 'ぇ'
+            | 'ぉ'
+            | 'ゃ'
+            | 'ゅ'
+            | 'ょ'
+            | 'ゎ'
+    )
+}
+
+fn is_kanji(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}' | '々')
+}
+
+fn hiragana_to_katakana(c: char) -> char {
+    match c {
+        '\u{3041}'..='\u{3096}' | 'ゝ' | 'ゞ' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+pub fn to_katakana(text: &str) -> String {
+    text.chars().map(hiragana_to_katakana).collect()
+}
+
+/// Count the morae in a katakana reading. Small kana (other than ッ) join the
+/// preceding mora; ッ, ン and ー each count as one mora.
+pub fn count_morae(kana: &str) -> usize {
+    kana.chars()
+        .filter(|&c| !is_small_kana(c) && c != ACCENT_MARK && c != ALT_ACCENT_MARK)
+        .count()
+}
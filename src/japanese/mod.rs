@@ -0,0 +1,5 @@
+pub mod dictionary;
+pub mod kana;
+
+pub use dictionary::ReadingDictionary;
+pub use kana::JapanesePhonemizer;
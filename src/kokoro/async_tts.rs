@@ -0,0 +1,318 @@
+use super::options::{CancellationToken, SynthesisOptions};
+use super::tts::{GeneratedAudio, KokoroTTS};
+use super::voice::VoiceStyle;
+use crate::error::{Error, Result};
+use crate::text::split_sentences;
+use futures_core::Stream;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+use std::thread;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc as async_mpsc, oneshot};
+use tracing::debug;
+
+/// Sentences synthesized ahead of a slow `AudioStream` consumer
+const STREAM_BUFFER: usize = 2;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Dedicated threads running blocking synthesis, so async callers never
+/// block their executor and don't need `spawn_blocking`. The threads are
+/// detached: dropping the pool closes the job channel, and each thread exits
+/// once its current job is done.
+struct InferenceThreads {
+    sender: mpsc::Sender<Job>,
+}
+
+impl InferenceThreads {
+    fn new(threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0..threads.max(1) {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("kokoro-inference-{}", index))
+                .spawn(move || loop {
+                    let job = lock(&receiver).recv();
+                    match job {
+                        // A panicking job drops its result sender, which
+                        // the caller sees as an error; keep the thread alive
+                        Ok(job) => {
+                            let _ = std::panic::catch_unwind(AssertUnwindSafe(job));
+                        }
+                        Err(_) => break,
+                    }
+                })
+                .unwrap_or_else(|e| panic!("failed to spawn inference thread: {}", e));
+        }
+
+        InferenceThreads { sender }
+    }
+
+    fn execute(&self, job: Job) {
+        // Only fails once every thread is gone; the dropped job then reports an error
+        let _ = self.sender.send(job);
+    }
+}
+
+/// Cancels its token when the future holding it is dropped
+struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Async facade over `KokoroTTS` for tokio (or any other executor).
+///
+/// Synthesis runs on a dedicated pool of inference threads. Returned futures
+/// and streams are `'static` and can be spawned. Dropping a future before its
+/// request starts cancels it; dropping a text synthesis future or an
+/// `AudioStream` later stops synthesis after the sentence in progress.
+/// Cloning is cheap and shares the engine and threads.
+#[derive(Clone)]
+pub struct AsyncKokoroTTS {
+    tts: Arc<KokoroTTS>,
+    threads: Arc<InferenceThreads>,
+}
+
+impl AsyncKokoroTTS {
+    /// Wrap an engine with one inference thread per available CPU.
+    pub fn new(tts: impl Into<Arc<KokoroTTS>>) -> Self {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        Self::with_threads(tts, threads)
+    }
+
+    pub fn with_threads(tts: impl Into<Arc<KokoroTTS>>, threads: usize) -> Self {
+        AsyncKokoroTTS {
+            tts: tts.into(),
+            threads: Arc::new(InferenceThreads::new(threads)),
+        }
+    }
+
+    /// The wrapped engine, for synchronous calls such as `phonemize`.
+    pub fn engine(&self) -> &Arc<KokoroTTS> {
+        &self.tts
+    }
+
+    /// Run `f` on an inference thread. It is skipped if the returned future
+    /// is dropped before a thread picks it up.
+    pub fn run<T, F>(&self, f: F) -> impl Future<Output = Result<T>> + Send + 'static
+    where
+        T: Send + 'static,
+        F: FnOnce(&KokoroTTS) -> Result<T> + Send + 'static,
+    {
+        self.run_cancellable(move |tts, _| f(tts))
+    }
+
+    /// Like `run`, with a token that is cancelled when the returned future
+    /// is dropped, so `f` can stop work already in progress.
+    fn run_cancellable<T, F>(&self, f: F) -> impl Future<Output = Result<T>> + Send + 'static
+    where
+        T: Send + 'static,
+        F: FnOnce(&KokoroTTS, CancellationToken) -> Result<T> + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let token = CancellationToken::new();
+        let guard = CancelOnDrop(token.clone());
+        let tts = Arc::clone(&self.tts);
+        self.threads.execute(Box::new(move || {
+            if sender.is_closed() {
+                debug!("request cancelled before synthesis");
+                return;
+            }
+            let _ = sender.send(f(&tts, token));
+        }));
+
+        async move {
+            let _guard = guard;
+            receiver.await.unwrap_or_else(|_| {
+                Err(Error::inference(
+                    "Inference thread stopped before returning a result",
+                ))
+            })
+        }
+    }
+
+    /// Synthesized one sentence at a time, like
+    /// `KokoroTTS::generate_speech_with_options`, so that dropping the future
+    /// stops synthesis after the sentence in progress.
+    pub fn generate_speech(
+        &self,
+        text: impl Into<String>,
+        voice_style: Arc<VoiceStyle>,
+        speed: f32,
+    ) -> impl Future<Output = Result<GeneratedAudio>> + Send + 'static {
+        self.generate_speech_with_options(text, voice_style, speed, SynthesisOptions::new())
+    }
+
+    pub fn generate_speech_with_options(
+        &self,
+        text: impl Into<String>,
+        voice_style: Arc<VoiceStyle>,
+        speed: f32,
+        options: SynthesisOptions,
+    ) -> impl Future<Output = Result<GeneratedAudio>> + Send + 'static {
+        let text = text.into();
+        self.run_cancellable(move |tts, dropped| {
+            let options = options.with_cancellation(dropped);
+            tts.generate_speech_with_options(&text, &voice_style, speed, &options)
+        })
+    }
+
+    pub fn generate_speech_from_phonemes(
+        &self,
+        phonemes: impl Into<String>,
+        voice_style: Arc<VoiceStyle>,
+        speed: f32,
+    ) -> impl Future<Output = Result<GeneratedAudio>> + Send + 'static {
+        let phonemes = phonemes.into();
+        self.run(move |tts| tts.generate_speech_from_phonemes(&phonemes, &voice_style, speed))
+    }
+
+    pub fn generate_from_tokens(
+        &self,
+        tokens: Vec<i64>,
+        voice_style: Arc<VoiceStyle>,
+        speed: f32,
+    ) -> impl Future<Output = Result<GeneratedAudio>> + Send + 'static {
+        self.run(move |tts| tts.generate_from_tokens(&tokens, &voice_style, speed))
+    }
+
+    /// Synthesize long text sentence by sentence, yielding each sentence's
+    /// audio as soon as it is ready. The stream ends after the first error.
+    ///
+    /// Each sentence is a separate job on the inference threads, submitted
+    /// once the stream has room for its audio, so a stream that isn't read
+    /// doesn't hold a thread.
+    pub fn stream_speech(
+        &self,
+        text: impl Into<String>,
+        voice_style: Arc<VoiceStyle>,
+        speed: f32,
+    ) -> AudioStream {
+        let text = text.into();
+        let (sender, receiver) = async_mpsc::channel(STREAM_BUFFER);
+        let parked = Arc::new(Mutex::new(None));
+
+        StreamProducer {
+            tts: Arc::clone(&self.tts),
+            threads: Arc::clone(&self.threads),
+            sentences: split_sentences(&text)
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+                .into_iter(),
+            voice_style,
+            speed,
+            sender,
+            parked: Arc::clone(&parked),
+        }
+        .submit();
+
+        AudioStream { receiver, parked }
+    }
+}
+
+/// Remaining sentences of an `AudioStream`. It moves between inference jobs,
+/// one per sentence, and waits in `parked` (off the threads) while the
+/// stream is full, until the consumer takes a chunk and submits it again.
+struct StreamProducer {
+    tts: Arc<KokoroTTS>,
+    threads: Arc<InferenceThreads>,
+    sentences: std::vec::IntoIter<String>,
+    voice_style: Arc<VoiceStyle>,
+    speed: f32,
+    sender: async_mpsc::Sender<Result<GeneratedAudio>>,
+    parked: Arc<Mutex<Option<StreamProducer>>>,
+}
+
+impl StreamProducer {
+    fn submit(self) {
+        let threads = Arc::clone(&self.threads);
+        threads.execute(Box::new(move || self.next_sentence()));
+    }
+
+    fn next_sentence(mut self) {
+        if self.sentences.as_slice().is_empty() {
+            return;
+        }
+
+        // Checked under the lock so a consumer that frees a slot right after
+        // a failed reservation finds the producer parked
+        let parked = Arc::clone(&self.parked);
+        let sender = self.sender.clone();
+        let mut slot = lock(&parked);
+        let permit = match sender.try_reserve() {
+            Ok(permit) => permit,
+            Err(TrySendError::Full(())) => {
+                *slot = Some(self);
+                return;
+            }
+            Err(TrySendError::Closed(())) => {
+                debug!("audio stream dropped, stopping synthesis");
+                return;
+            }
+        };
+        drop(slot);
+
+        let Some(sentence) = self.sentences.next() else {
+            return;
+        };
+        let result = self
+            .tts
+            .generate_speech(&sentence, &self.voice_style, self.speed);
+        let failed = result.is_err();
+        permit.send(result);
+        if !failed {
+            self.submit();
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Audio chunks (one per sentence) from `AsyncKokoroTTS::stream_speech`.
+/// Implements `futures_core::Stream`; `next` is provided for callers without
+/// `StreamExt`.
+pub struct AudioStream {
+    receiver: async_mpsc::Receiver<Result<GeneratedAudio>>,
+    parked: Arc<Mutex<Option<StreamProducer>>>,
+}
+
+impl AudioStream {
+    pub async fn next(&mut self) -> Option<Result<GeneratedAudio>> {
+        std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+impl Stream for AudioStream {
+    type Item = Result<GeneratedAudio>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let chunk = self.receiver.poll_recv(cx);
+        if let Poll::Ready(Some(_)) = chunk {
+            // A slot is free again; resume a producer waiting for one
+            let producer = lock(&self.parked).take();
+            if let Some(producer) = producer {
+                producer.submit();
+            }
+        }
+        chunk
+    }
+}
+
+impl Drop for AudioStream {
+    fn drop(&mut self) {
+        // The parked producer refers back to `parked`; drop it to free both
+        let producer = lock(&self.parked).take();
+        drop(producer);
+    }
+}
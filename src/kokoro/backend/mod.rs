@@ -0,0 +1,179 @@
+#[cfg(feature = "ort")]
+mod onnxruntime;
+#[cfg(feature = "tract")]
+mod tract;
+
+#[cfg(not(any(feature = "ort", feature = "tract")))]
+compile_error!("kokoroxide needs an inference backend: enable the `ort` or `tract` feature");
+
+use super::model::ModelSource;
+use super::variant::ModelVariant;
+use crate::error::{Error, Result};
+use half::f16;
+use ndarray::ArrayD;
+use std::fmt;
+#[cfg(feature = "ort")]
+use std::sync::Arc;
+
+#[cfg(feature = "ort")]
+pub(crate) use onnxruntime::{copy_level, OrtOptions};
+
+/// Library that runs the Kokoro model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Backend {
+    /// ONNX Runtime through `ort` (`ort` feature, on by default)
+    #[cfg(feature = "ort")]
+    #[cfg_attr(feature = "ort", default)]
+    Ort,
+    /// tract, pure Rust on the CPU (`tract` feature)
+    #[cfg(feature = "tract")]
+    #[cfg_attr(not(feature = "ort"), default)]
+    Tract,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match *self {
+            #[cfg(feature = "ort")]
+            Backend::Ort => "onnxruntime",
+            #[cfg(feature = "tract")]
+            Backend::Tract => "tract",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Element type of a model input or output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ElementType {
+    Int32,
+    Int64,
+    Float16,
+    Float32,
+    Float64,
+    /// Anything Kokoro exports don't use
+    Other,
+}
+
+/// Name, element type and shape of a model input or output
+#[derive(Debug, Clone)]
+pub(crate) struct TensorInfo {
+    pub(crate) name: String,
+    pub(crate) dtype: ElementType,
+    /// `None` for dynamic dimensions
+    pub(crate) dims: Vec<Option<i64>>,
+}
+
+/// Tensor data passed to and returned from a backend
+#[derive(Debug, Clone)]
+pub(crate) enum TensorData {
+    Int64(ArrayD<i64>),
+    Int32(ArrayD<i32>),
+    Float16(ArrayD<f16>),
+    Float32(ArrayD<f32>),
+    Float64(ArrayD<f64>),
+}
+
+impl TensorData {
+    /// Float data as f32, widening fp16; `None` for integer tensors
+    pub(crate) fn to_f32(&self) -> Option<ArrayD<f32>> {
+        match self {
+            TensorData::Float16(array) => Some(array.mapv(f16::to_f32)),
+            TensorData::Float32(array) => Some(array.clone()),
+            TensorData::Float64(array) => Some(array.mapv(|value| value as f32)),
+            TensorData::Int64(_) | TensorData::Int32(_) => None,
+        }
+    }
+
+    pub(crate) fn element_type(&self) -> ElementType {
+        match self {
+            TensorData::Int64(_) => ElementType::Int64,
+            TensorData::Int32(_) => ElementType::Int32,
+            TensorData::Float16(_) => ElementType::Float16,
+            TensorData::Float32(_) => ElementType::Float32,
+            TensorData::Float64(_) => ElementType::Float64,
+        }
+    }
+}
+
+/// A loaded model that can be run with owned tensors.
+///
+/// Inputs are passed in `inputs()` order with the declared element types;
+/// outputs come back in `outputs()` order. Outputs of an `Other` element
+/// type are returned empty, since synthesis never reads them.
+pub(crate) trait InferenceBackend: Send + Sync {
+    fn inputs(&self) -> &[TensorInfo];
+
+    fn outputs(&self) -> &[TensorInfo];
+
+    fn run(&self, inputs: Vec<TensorData>) -> Result<Vec<TensorData>>;
+
+    /// Where the model runs, e.g. `"CUDAExecutionProvider"` or `"tract"`
+    fn execution_provider(&self) -> &'static str;
+}
+
+/// State shared by engines loaded together, e.g. the ONNX Runtime
+/// environment
+#[derive(Clone)]
+pub(crate) struct BackendEnvironment {
+    #[cfg(feature = "ort")]
+    ort: Arc<ort::Environment>,
+}
+
+impl BackendEnvironment {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(BackendEnvironment {
+            #[cfg(feature = "ort")]
+            ort: onnxruntime::create_environment()?,
+        })
+    }
+}
+
+/// Backend-specific settings from `TTSConfig`
+pub(crate) struct LoadOptions {
+    pub(crate) backend: Backend,
+    pub(crate) model_variant: ModelVariant,
+    #[cfg(feature = "ort")]
+    pub(crate) ort: OrtOptions,
+}
+
+/// Load the model for `sessions` engines that may run concurrently
+#[cfg_attr(not(feature = "ort"), allow(unused_variables))]
+pub(crate) fn load(
+    env: &BackendEnvironment,
+    options: LoadOptions,
+    model: &ModelSource,
+    sessions: usize,
+) -> Result<Vec<Box<dyn InferenceBackend>>> {
+    match options.backend {
+        #[cfg(feature = "ort")]
+        Backend::Ort => Ok(onnxruntime::load(
+            &env.ort,
+            options.ort,
+            options.model_variant,
+            model,
+            sessions,
+        )?
+        .into_iter()
+        .map(|session| Box::new(session) as Box<dyn InferenceBackend>)
+        .collect()),
+        #[cfg(feature = "tract")]
+        Backend::Tract => {
+            // A tract plan can run concurrently, so the copies share it
+            let loaded = tract::TractModel::load(model, options.model_variant)?;
+            Ok((0..sessions)
+                .map(|_| Box::new(loaded.clone()) as Box<dyn InferenceBackend>)
+                .collect())
+        }
+    }
+}
+
+/// Thread counts are passed to ONNX Runtime as `i16`
+pub(crate) fn thread_count(threads: Option<usize>, kind: &str) -> Result<Option<i16>> {
+    threads
+        .map(|threads| {
+            i16::try_from(threads)
+                .map_err(|_| Error::config(format!("Too many {} threads: {}", kind, threads)))
+        })
+        .transpose()
+}
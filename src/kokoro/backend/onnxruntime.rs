@@ -0,0 +1,511 @@
+use super::{ElementType, InferenceBackend, TensorData, TensorInfo};
+use crate::error::{Error, Result};
+use crate::kokoro::cache::optimized_model;
+use crate::kokoro::model::ModelSource;
+use crate::kokoro::ort_api::{ort_call, ort_path, ort_release};
+use crate::kokoro::providers::{register_provider, CPU_PROVIDER};
+use crate::kokoro::variant::ModelVariant;
+use half::f16;
+use ndarray::{ArrayD, CowArray, IxDyn};
+use ort::session::SessionPointerHolder;
+use ort::{sys, Environment, ExecutionProvider, GraphOptimizationLevel, Value};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::path::Path;
+use std::ptr;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// ONNX Runtime settings from `TTSConfig`
+pub(crate) struct OrtOptions {
+    pub(crate) graph_level: GraphOptimizationLevel,
+    pub(crate) optimized_model_cache: Option<String>,
+    pub(crate) intra_op_threads: Option<i16>,
+    pub(crate) inter_op_threads: Option<i16>,
+    pub(crate) parallel_execution: bool,
+    pub(crate) memory_arena: bool,
+    pub(crate) memory_pattern: bool,
+    pub(crate) thread_spinning: bool,
+    pub(crate) execution_providers: Vec<ExecutionProvider>,
+    pub(crate) strict_execution_providers: bool,
+}
+
+/// ONNX Runtime environment for engines
+pub(super) fn create_environment() -> Result<Arc<Environment>> {
+    let env = Environment::builder()
+        .with_name("kokoro_tts")
+        .build()
+        .map_err(|e| {
+            Error::inference("Failed to create ONNX Runtime environment").with_source(e)
+        })?;
+    Ok(Arc::new(env))
+}
+
+/// Create `sessions` sessions for the model in `env`
+pub(super) fn load(
+    env: &Arc<Environment>,
+    options: OrtOptions,
+    model_variant: ModelVariant,
+    model: &ModelSource,
+    sessions: usize,
+) -> Result<Vec<OrtSession>> {
+    let OrtOptions {
+        graph_level,
+        optimized_model_cache,
+        intra_op_threads,
+        inter_op_threads,
+        parallel_execution,
+        memory_arena,
+        memory_pattern,
+        thread_spinning,
+        execution_providers,
+        strict_execution_providers,
+    } = options;
+
+    let session_options = SessionOptions::new()?;
+    // SAFETY: the options object is alive for the call
+    let provider_name = unsafe {
+        register_provider(
+            session_options.0,
+            &execution_providers,
+            strict_execution_providers,
+        )?
+    };
+
+    let cached;
+    let (model, graph_level) = match optimized_model_cache {
+        Some(dir) if !matches!(graph_level, GraphOptimizationLevel::Disable) => {
+            // Provider-specific optimizations can't be saved, and Level3
+            // layout transforms depend on the CPU's instruction set; cache
+            // the portable ones and let the session apply the rest
+            let cache_level = match graph_level {
+                _ if provider_name != CPU_PROVIDER => GraphOptimizationLevel::Level1,
+                GraphOptimizationLevel::Level3 => GraphOptimizationLevel::Level2,
+                ref level => copy_level(level),
+            };
+            match optimized_model(env, model, cache_level, Path::new(&dir)) {
+                Ok(path) => {
+                    let complete = provider_name == CPU_PROVIDER
+                        && !matches!(graph_level, GraphOptimizationLevel::Level3);
+                    let load_level = if complete {
+                        GraphOptimizationLevel::Disable
+                    } else {
+                        graph_level
+                    };
+                    cached = ModelSource::File(path.to_string_lossy().into_owned());
+                    (&cached, load_level)
+                }
+                Err(e) => {
+                    warn!(error = %e, "optimized model cache unavailable");
+                    (model, graph_level)
+                }
+            }
+        }
+        _ => (model, graph_level),
+    };
+
+    // SAFETY: the options object is alive for the whole block
+    unsafe {
+        let options = session_options.0;
+        let level = sys::GraphOptimizationLevel::from(copy_level(&graph_level));
+        let mode = if parallel_execution {
+            sys::ExecutionMode::ORT_PARALLEL
+        } else {
+            sys::ExecutionMode::ORT_SEQUENTIAL
+        };
+        ort_call!(SetSessionGraphOptimizationLevel(options, level))
+            .and_then(|()| ort_call!(SetSessionExecutionMode(options, mode)))
+            .and_then(|()| match memory_pattern {
+                true => ort_call!(EnableMemPattern(options)),
+                false => ort_call!(DisableMemPattern(options)),
+            })
+            // The CPU provider's arena; ONNX Runtime enables it by default
+            .and_then(|()| match memory_arena {
+                true => ort_call!(EnableCpuMemArena(options)),
+                false => ort_call!(DisableCpuMemArena(options)),
+            })
+            .and_then(|()| match intra_op_threads {
+                Some(threads) => ort_call!(SetIntraOpNumThreads(options, threads.into())),
+                None => Ok(()),
+            })
+            .and_then(|()| match inter_op_threads {
+                Some(threads) => ort_call!(SetInterOpNumThreads(options, threads.into())),
+                None => Ok(()),
+            })
+            .map_err(|e| Error::inference(format!("Failed to configure session: {}", e)))?;
+    }
+    if !thread_spinning {
+        session_options.add_config_entry("session.intra_op.allow_spinning", "0")?;
+        session_options.add_config_entry("session.inter_op.allow_spinning", "0")?;
+    }
+    if let ModelSource::Memory(_) = model {
+        // As ort does for in-memory models: use the bytes in place rather
+        // than copying them (the session keeps them alive)
+        session_options.add_config_entry("session.use_ort_model_bytes_directly", "1")?;
+        session_options.add_config_entry("session.use_ort_model_bytes_for_initializers", "1")?;
+    }
+
+    let mut session_list = Vec::with_capacity(sessions);
+    for index in 0..sessions {
+        let load_start = Instant::now();
+        let session = OrtSession::load(env, &session_options, model, provider_name)?;
+        info!(
+            model = %model,
+            variant = %model_variant,
+            provider = provider_name,
+            session = index,
+            elapsed_ms = load_start.elapsed().as_secs_f64() * 1000.0,
+            "model loaded"
+        );
+        session_list.push(session);
+    }
+    Ok(session_list)
+}
+
+/// `GraphOptimizationLevel` is neither `Copy` nor `Clone`
+pub(crate) fn copy_level(level: &GraphOptimizationLevel) -> GraphOptimizationLevel {
+    match level {
+        GraphOptimizationLevel::Disable => GraphOptimizationLevel::Disable,
+        GraphOptimizationLevel::Level1 => GraphOptimizationLevel::Level1,
+        GraphOptimizationLevel::Level2 => GraphOptimizationLevel::Level2,
+        GraphOptimizationLevel::Level3 => GraphOptimizationLevel::Level3,
+    }
+}
+
+/// Session options set through the C API: ort's `SessionBuilder` can't add
+/// session config entries such as `session.intra_op.allow_spinning`
+struct SessionOptions(*mut sys::OrtSessionOptions);
+
+impl SessionOptions {
+    fn new() -> Result<Self> {
+        let mut options: *mut sys::OrtSessionOptions = ptr::null_mut();
+        // SAFETY: ONNX Runtime writes a new options object to `options`
+        unsafe { ort_call!(CreateSessionOptions(&mut options)) }
+            .map_err(|e| Error::inference(format!("Failed to create session options: {}", e)))?;
+        Ok(SessionOptions(options))
+    }
+
+    fn add_config_entry(&self, key: &str, value: &str) -> Result<()> {
+        let config_error = |message: String| {
+            Error::inference(format!(
+                "Failed to set session option {}={}: {}",
+                key, value, message
+            ))
+        };
+        let c_key = CString::new(key).map_err(|e| config_error(e.to_string()))?;
+        let c_value = CString::new(value).map_err(|e| config_error(e.to_string()))?;
+        // SAFETY: the options object and both strings outlive the call
+        unsafe {
+            ort_call!(AddSessionConfigEntry(
+                self.0,
+                c_key.as_ptr(),
+                c_value.as_ptr()
+            ))
+        }
+        .map_err(config_error)
+    }
+}
+
+impl Drop for SessionOptions {
+    fn drop(&mut self) {
+        // SAFETY: created in `new` and released only here
+        unsafe { ort_release!(ReleaseSessionOptions(self.0)) }
+    }
+}
+
+/// An ONNX Runtime session, holding on to the model bytes when it was
+/// created from memory (ONNX Runtime may reference them for the session's
+/// lifetime).
+pub(super) struct OrtSession {
+    // Declared first so the session is released before the bytes
+    session: Arc<SessionPointerHolder>,
+    _bytes: Option<Arc<std::borrow::Cow<'static, [u8]>>>,
+    inputs: Vec<TensorInfo>,
+    outputs: Vec<TensorInfo>,
+    input_names: Vec<CString>,
+    output_names: Vec<CString>,
+    provider: &'static str,
+}
+
+impl OrtSession {
+    fn load(
+        env: &Environment,
+        options: &SessionOptions,
+        source: &ModelSource,
+        provider: &'static str,
+    ) -> Result<Self> {
+        let load_error = |message: String| {
+            Error::inference(format!("Failed to load model {}: {}", source, message))
+        };
+
+        let mut session: *mut sys::OrtSession = ptr::null_mut();
+        let bytes = match source {
+            ModelSource::File(path) => {
+                let path = ort_path(Path::new(path));
+                // SAFETY: the environment, path and options outlive the call
+                unsafe {
+                    ort_call!(CreateSession(
+                        env.ptr(),
+                        path.as_ptr(),
+                        options.0,
+                        &mut session
+                    ))
+                }
+                .map_err(load_error)?;
+                None
+            }
+            ModelSource::Memory(bytes) => {
+                // SAFETY: the bytes live on the heap (or in static memory), are
+                // never mutated, and the Arc stored next to the session keeps
+                // them alive until after the session is dropped.
+                unsafe {
+                    ort_call!(CreateSessionFromArray(
+                        env.ptr(),
+                        bytes.as_ptr() as *const sys::c_void,
+                        bytes.len() as _,
+                        options.0,
+                        &mut session
+                    ))
+                }
+                .map_err(load_error)?;
+                Some(Arc::clone(bytes))
+            }
+        };
+        let session = Arc::new(SessionPointerHolder { inner: session });
+
+        let inputs = io_info(&session, Io::Input).map_err(load_error)?;
+        let outputs = io_info(&session, Io::Output).map_err(load_error)?;
+        let names = |tensors: &[TensorInfo]| {
+            tensors
+                .iter()
+                .map(|tensor| CString::new(tensor.name.as_str()))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| load_error(e.to_string()))
+        };
+        Ok(OrtSession {
+            input_names: names(&inputs)?,
+            output_names: names(&outputs)?,
+            session,
+            _bytes: bytes,
+            inputs,
+            outputs,
+            provider,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Io {
+    Input,
+    Output,
+}
+
+/// Names, element types and dimensions of a session's inputs or outputs
+fn io_info(session: &SessionPointerHolder, io: Io) -> std::result::Result<Vec<TensorInfo>, String> {
+    let session = session.inner;
+    let allocator = default_allocator()?;
+    let mut count = 0;
+    // SAFETY: `session` is a live session and every object ONNX Runtime
+    // returns below is released once, after its contents are copied
+    unsafe {
+        match io {
+            Io::Input => ort_call!(SessionGetInputCount(session, &mut count))?,
+            Io::Output => ort_call!(SessionGetOutputCount(session, &mut count))?,
+        }
+
+        (0..count)
+            .map(|index| {
+                let mut name: *mut c_char = ptr::null_mut();
+                match io {
+                    Io::Input => {
+                        ort_call!(SessionGetInputName(session, index, allocator, &mut name))?
+                    }
+                    Io::Output => {
+                        ort_call!(SessionGetOutputName(session, index, allocator, &mut name))?
+                    }
+                }
+                let owned_name = CStr::from_ptr(name).to_string_lossy().into_owned();
+                ort_call!(AllocatorFree(allocator, name as *mut sys::c_void))?;
+
+                let mut type_info: *mut sys::OrtTypeInfo = ptr::null_mut();
+                match io {
+                    Io::Input => {
+                        ort_call!(SessionGetInputTypeInfo(session, index, &mut type_info))?
+                    }
+                    Io::Output => {
+                        ort_call!(SessionGetOutputTypeInfo(session, index, &mut type_info))?
+                    }
+                }
+                let shape = tensor_shape(type_info);
+                ort_release!(ReleaseTypeInfo(type_info));
+                let (dtype, dims) = shape?;
+                Ok(TensorInfo {
+                    name: owned_name,
+                    dtype,
+                    dims,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Element type and dimensions (`None` where dynamic) of a tensor type;
+/// other types get `ElementType::Other` and no dimensions
+///
+/// # Safety
+///
+/// `type_info` must be a live `OrtTypeInfo`.
+unsafe fn tensor_shape(
+    type_info: *const sys::OrtTypeInfo,
+) -> std::result::Result<(ElementType, Vec<Option<i64>>), String> {
+    let mut tensor: *const sys::OrtTensorTypeAndShapeInfo = ptr::null();
+    ort_call!(CastTypeInfoToTensorInfo(type_info, &mut tensor))?;
+    if tensor.is_null() {
+        return Ok((ElementType::Other, vec![]));
+    }
+
+    let mut dtype = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_UNDEFINED;
+    ort_call!(GetTensorElementType(tensor, &mut dtype))?;
+    let mut rank = 0;
+    ort_call!(GetDimensionsCount(tensor, &mut rank))?;
+    let mut dims = vec![0i64; rank as _];
+    ort_call!(GetDimensions(tensor, dims.as_mut_ptr(), rank))?;
+    Ok((
+        element_type(dtype),
+        dims.into_iter()
+            .map(|dim| (dim >= 0).then_some(dim))
+            .collect(),
+    ))
+}
+
+/// ONNX Runtime's default CPU allocator, owned by the library
+fn default_allocator() -> std::result::Result<*mut sys::OrtAllocator, String> {
+    let mut allocator: *mut sys::OrtAllocator = ptr::null_mut();
+    // SAFETY: ONNX Runtime writes a pointer to its static allocator
+    unsafe { ort_call!(GetAllocatorWithDefaultOptions(&mut allocator)) }?;
+    Ok(allocator)
+}
+
+impl InferenceBackend for OrtSession {
+    fn inputs(&self) -> &[TensorInfo] {
+        &self.inputs
+    }
+
+    fn outputs(&self) -> &[TensorInfo] {
+        &self.outputs
+    }
+
+    fn run(&self, inputs: Vec<TensorData>) -> Result<Vec<TensorData>> {
+        let run_error =
+            |message: String| Error::inference(format!("Model run failed: {}", message));
+        let allocator = default_allocator().map_err(run_error)?;
+        let arrays: Vec<InputArray> = inputs.into_iter().map(InputArray::from).collect();
+        let values = arrays
+            .iter()
+            .map(|array| array.value(allocator))
+            .collect::<Result<Vec<_>>>()?;
+
+        let input_names: Vec<*const c_char> =
+            self.input_names.iter().map(|name| name.as_ptr()).collect();
+        let output_names: Vec<*const c_char> =
+            self.output_names.iter().map(|name| name.as_ptr()).collect();
+        let input_values: Vec<*const sys::OrtValue> =
+            values.iter().map(|value| value.ptr() as *const _).collect();
+        let mut output_values: Vec<*mut sys::OrtValue> = vec![ptr::null_mut(); output_names.len()];
+        // SAFETY: the names, input values and output slots outlive the call,
+        // and ONNX Runtime fills every output slot on success
+        unsafe {
+            ort_call!(Run(
+                self.session.inner,
+                ptr::null(),
+                input_names.as_ptr(),
+                input_values.as_ptr(),
+                input_values.len() as _,
+                output_names.as_ptr(),
+                output_names.len() as _,
+                output_values.as_mut_ptr()
+            ))
+        }
+        .map_err(run_error)?;
+
+        // Wrapped so each output is released when dropped
+        let outputs: Vec<Value> = output_values
+            .into_iter()
+            .map(|value| Value::from_raw(value, Arc::clone(&self.session)))
+            .collect();
+        outputs
+            .iter()
+            .zip(&self.outputs)
+            .map(|(value, info)| {
+                extract(value, info.dtype).map_err(|e| {
+                    Error::output(format!("Failed to extract output '{}'", info.name))
+                        .with_source(e)
+                })
+            })
+            .collect()
+    }
+
+    fn execution_provider(&self) -> &'static str {
+        self.provider
+    }
+}
+
+/// Input data in the form `Value::from_array` borrows
+enum InputArray<'a> {
+    Int64(CowArray<'a, i64, IxDyn>),
+    Int32(CowArray<'a, i32, IxDyn>),
+    Float16(CowArray<'a, f16, IxDyn>),
+    Float32(CowArray<'a, f32, IxDyn>),
+    Float64(CowArray<'a, f64, IxDyn>),
+}
+
+impl From<TensorData> for InputArray<'_> {
+    fn from(tensor: TensorData) -> Self {
+        match tensor {
+            TensorData::Int64(array) => InputArray::Int64(CowArray::from(array)),
+            TensorData::Int32(array) => InputArray::Int32(CowArray::from(array)),
+            TensorData::Float16(array) => InputArray::Float16(CowArray::from(array)),
+            TensorData::Float32(array) => InputArray::Float32(CowArray::from(array)),
+            TensorData::Float64(array) => InputArray::Float64(CowArray::from(array)),
+        }
+    }
+}
+
+impl<'a> InputArray<'a> {
+    /// The value borrows the array, so it can't outlive it
+    fn value(&'a self, allocator: *mut sys::OrtAllocator) -> Result<Value<'a>> {
+        let value = match self {
+            InputArray::Int64(array) => Value::from_array(allocator, array),
+            InputArray::Int32(array) => Value::from_array(allocator, array),
+            InputArray::Float16(array) => Value::from_array(allocator, array),
+            InputArray::Float32(array) => Value::from_array(allocator, array),
+            InputArray::Float64(array) => Value::from_array(allocator, array),
+        };
+        value.map_err(|e| Error::inference("Failed to create input tensors").with_source(e))
+    }
+}
+
+/// Copy an output out of ONNX Runtime's memory
+fn extract(value: &Value, dtype: ElementType) -> std::result::Result<TensorData, ort::OrtError> {
+    Ok(match dtype {
+        ElementType::Int64 => TensorData::Int64(value.try_extract::<i64>()?.view().to_owned()),
+        ElementType::Int32 => TensorData::Int32(value.try_extract::<i32>()?.view().to_owned()),
+        ElementType::Float16 => TensorData::Float16(value.try_extract::<f16>()?.view().to_owned()),
+        ElementType::Float64 => TensorData::Float64(value.try_extract::<f64>()?.view().to_owned()),
+        ElementType::Float32 => TensorData::Float32(value.try_extract::<f32>()?.view().to_owned()),
+        ElementType::Other => TensorData::Float32(ArrayD::zeros(IxDyn(&[0]))),
+    })
+}
+
+fn element_type(dtype: sys::ONNXTensorElementDataType) -> ElementType {
+    use sys::ONNXTensorElementDataType::*;
+    match dtype {
+        ONNX_TENSOR_ELEMENT_DATA_TYPE_INT32 => ElementType::Int32,
+        ONNX_TENSOR_ELEMENT_DATA_TYPE_INT64 => ElementType::Int64,
+        ONNX_TENSOR_ELEMENT_DATA_TYPE_FLOAT16 => ElementType::Float16,
+        ONNX_TENSOR_ELEMENT_DATA_TYPE_FLOAT => ElementType::Float32,
+        ONNX_TENSOR_ELEMENT_DATA_TYPE_DOUBLE => ElementType::Float64,
+        _ => ElementType::Other,
+    }
+}
@@ -0,0 +1,183 @@
+use super::{ElementType, InferenceBackend, TensorData, TensorInfo};
+use crate::error::{Error, Result};
+use crate::kokoro::model::ModelSource;
+use crate::kokoro::variant::ModelVariant;
+use half::f16;
+use ndarray::{ArrayD, ArrayViewD, IxDyn};
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info, warn};
+use tract_onnx::prelude::*;
+use tract_onnx::tract_hir::infer::Factoid;
+
+type TypedPlan = TypedSimplePlan<TypedModel>;
+type InferencePlan = InferenceSimplePlan<InferenceModel>;
+
+enum Plan {
+    Optimized(TypedPlan),
+    /// Shapes resolved on every run, for graphs tract can't type ahead of
+    /// time (output lengths that depend on predicted durations)
+    Inference(InferencePlan),
+}
+
+/// A Kokoro model run by tract on the CPU. Clones share the plan.
+#[derive(Clone)]
+pub(super) struct TractModel {
+    plan: Arc<Plan>,
+    inputs: Arc<[TensorInfo]>,
+    outputs: Arc<[TensorInfo]>,
+}
+
+impl TractModel {
+    pub(super) fn load(source: &ModelSource, model_variant: ModelVariant) -> Result<Self> {
+        let load_error = |e: TractError| {
+            Error::inference(format!("Failed to load model {}", source)).with_source(e)
+        };
+        let load_start = Instant::now();
+
+        let onnx = tract_onnx::onnx();
+        let model = match source {
+            ModelSource::File(path) => onnx.model_for_path(path),
+            ModelSource::Memory(bytes) => onnx.model_for_read(&mut Cursor::new(&bytes[..])),
+        }
+        .map_err(load_error)?;
+
+        let inputs = model
+            .input_outlets()
+            .map_err(load_error)?
+            .iter()
+            .enumerate()
+            .map(|(index, outlet)| {
+                let fact = model.input_fact(index)?;
+                Ok(tensor_info(&model.node(outlet.node).name, fact))
+            })
+            .collect::<TractResult<Vec<_>>>()
+            .map_err(load_error)?;
+        let outputs = model
+            .output_outlets()
+            .map_err(load_error)?
+            .iter()
+            .enumerate()
+            .map(|(index, outlet)| {
+                let fact = model.output_fact(index)?;
+                let name = model
+                    .outlet_label(*outlet)
+                    .unwrap_or(model.node(outlet.node).name.as_str());
+                Ok(tensor_info(name, fact))
+            })
+            .collect::<TractResult<Vec<_>>>()
+            .map_err(load_error)?;
+
+        let plan = match model.clone().into_optimized() {
+            Ok(optimized) => Plan::Optimized(optimized.into_runnable().map_err(load_error)?),
+            Err(e) => {
+                warn!(error = %e, "tract could not optimize the model, running it unoptimized");
+                Plan::Inference(model.into_runnable().map_err(load_error)?)
+            }
+        };
+
+        info!(
+            model = %source,
+            variant = %model_variant,
+            provider = "tract",
+            optimized = matches!(plan, Plan::Optimized(_)),
+            elapsed_ms = load_start.elapsed().as_secs_f64() * 1000.0,
+            "model loaded"
+        );
+        Ok(TractModel {
+            plan: Arc::new(plan),
+            inputs: inputs.into(),
+            outputs: outputs.into(),
+        })
+    }
+}
+
+impl InferenceBackend for TractModel {
+    fn inputs(&self) -> &[TensorInfo] {
+        &self.inputs
+    }
+
+    fn outputs(&self) -> &[TensorInfo] {
+        &self.outputs
+    }
+
+    fn run(&self, inputs: Vec<TensorData>) -> Result<Vec<TensorData>> {
+        let inputs = inputs
+            .into_iter()
+            .map(|tensor| to_tract(tensor).map(IntoTValue::into_tvalue))
+            .collect::<TractResult<TVec<TValue>>>()
+            .map_err(|e| Error::inference("Failed to create input tensors").with_source(e))?;
+
+        let outputs = match self.plan.as_ref() {
+            Plan::Optimized(plan) => plan.run(inputs),
+            Plan::Inference(plan) => plan.run(inputs),
+        }
+        .map_err(|e| Error::inference("Model run failed").with_source(e))?;
+
+        outputs
+            .iter()
+            .zip(self.outputs.iter())
+            .map(|(value, info)| {
+                from_tract(value).map_err(|e| {
+                    Error::output(format!("Failed to extract output '{}'", info.name))
+                        .with_source(e)
+                })
+            })
+            .collect()
+    }
+
+    fn execution_provider(&self) -> &'static str {
+        "tract"
+    }
+}
+
+fn tensor_info(name: &str, fact: &InferenceFact) -> TensorInfo {
+    let dtype = match fact.datum_type.concretize() {
+        Some(DatumType::I32) => ElementType::Int32,
+        Some(DatumType::I64) => ElementType::Int64,
+        Some(DatumType::F16) => ElementType::Float16,
+        Some(DatumType::F32) => ElementType::Float32,
+        Some(DatumType::F64) => ElementType::Float64,
+        _ => ElementType::Other,
+    };
+    let dims = fact
+        .shape
+        .dims()
+        .map(|dim| dim.concretize().and_then(|dim| dim.to_i64().ok()))
+        .collect();
+    TensorInfo {
+        name: name.to_string(),
+        dtype,
+        dims,
+    }
+}
+
+fn to_tract(tensor: TensorData) -> TractResult<Tensor> {
+    Ok(match tensor {
+        TensorData::Int64(array) => array.into(),
+        TensorData::Int32(array) => array.into(),
+        // Narrowed by tract, so its f16 type need not be the one from `half`
+        TensorData::Float16(array) => Tensor::from(array.mapv(f16::to_f32))
+            .cast_to_dt(DatumType::F16)?
+            .into_owned(),
+        TensorData::Float32(array) => array.into(),
+        TensorData::Float64(array) => array.into(),
+    })
+}
+
+fn from_tract(tensor: &Tensor) -> TractResult<TensorData> {
+    let owned = |array: ArrayViewD<'_, f32>| TensorData::Float32(array.to_owned());
+    Ok(match tensor.datum_type() {
+        DatumType::I64 => TensorData::Int64(tensor.to_array_view::<i64>()?.to_owned()),
+        DatumType::I32 => TensorData::Int32(tensor.to_array_view::<i32>()?.to_owned()),
+        DatumType::F64 => TensorData::Float64(tensor.to_array_view::<f64>()?.to_owned()),
+        DatumType::F32 => owned(tensor.to_array_view::<f32>()?),
+        DatumType::F16 => {
+            let widened = tensor.cast_to::<f32>()?;
+            owned(widened.to_array_view::<f32>()?)
+        }
+        // Not read by synthesis; see `InferenceBackend`
+        _ => TensorData::Float32(ArrayD::zeros(IxDyn(&[0]))),
+    })
+}
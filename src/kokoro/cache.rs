@@ -0,0 +1,229 @@
+use super::model::ModelSource;
+use super::ort_api::{ort_call, ort_path, ort_release};
+use crate::error::{Error, Result};
+use ort::{sys, Environment, GraphOptimizationLevel};
+use std::ffi::CStr;
+use std::fs::{File, Metadata};
+use std::hash::Hasher;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::time::{Instant, UNIX_EPOCH};
+use tracing::{debug, info};
+
+/// The copy of `source` optimized at `level` in `dir`, created on first use.
+///
+/// The file name is a hash of the model's bytes, the ONNX Runtime build and
+/// the level, so a new model or runtime never picks up a stale graph. For a
+/// model file the content hash is remembered in a sidecar next to the cache
+/// and only computed again when the file's metadata changes.
+pub(crate) fn optimized_model(
+    env: &Environment,
+    source: &ModelSource,
+    level: GraphOptimizationLevel,
+    dir: &Path,
+) -> Result<PathBuf> {
+    let level = sys::GraphOptimizationLevel::from(level);
+    std::fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+
+    let mut hasher = Fnv64::default();
+    match source {
+        ModelSource::File(path) => hasher.write(&file_hash(Path::new(path), dir)?.to_le_bytes()),
+        ModelSource::Memory(bytes) => hasher.write(bytes),
+    }
+    hasher.write(runtime_build().as_bytes());
+    hasher.write(&(level as i32).to_le_bytes());
+
+    let cached = dir.join(format!("kokoro-{:016x}.onnx", hasher.finish()));
+    if cached.is_file() {
+        debug!(path = %cached.display(), "optimized model cache hit");
+        return Ok(cached);
+    }
+
+    // Write under a temporary name so concurrent starts never load a
+    // half-written graph
+    let partial = cached.with_extension(format!("onnx.{}.tmp", std::process::id()));
+    let start = Instant::now();
+    let optimized = save_optimized(env, source, level, &partial);
+    if let Err(e) = optimized {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+    std::fs::rename(&partial, &cached).map_err(|e| Error::io(&cached, e))?;
+
+    info!(
+        path = %cached.display(),
+        elapsed_ms = start.elapsed().as_secs_f64() * 1000.0,
+        "optimized model cached"
+    );
+    Ok(cached)
+}
+
+/// Content hash of the model file at `path`. The hash is stored in `dir`
+/// under the file's canonical path together with its metadata, and reused
+/// while the metadata is unchanged.
+fn file_hash(path: &Path, dir: &Path) -> Result<u64> {
+    let canonical = std::fs::canonicalize(path).map_err(|e| Error::io(path, e))?;
+    let metadata = std::fs::metadata(&canonical).map_err(|e| Error::io(path, e))?;
+    let stamp = file_stamp(&metadata);
+
+    let mut path_hasher = Fnv64::default();
+    path_hasher.write(canonical.to_string_lossy().as_bytes());
+    let sidecar = dir.join(format!("kokoro-{:016x}.source", path_hasher.finish()));
+    let remembered = std::fs::read_to_string(&sidecar).ok().and_then(|text| {
+        let (recorded, hash) = text.trim_end().rsplit_once(' ')?;
+        (recorded == stamp)
+            .then(|| u64::from_str_radix(hash, 16).ok())
+            .flatten()
+    });
+    if let Some(hash) = remembered {
+        return Ok(hash);
+    }
+
+    let start = Instant::now();
+    let mut file = File::open(&canonical).map_err(|e| Error::io(path, e))?;
+    let mut hasher = Fnv64::default();
+    let mut buffer = vec![0; 1 << 20];
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => hasher.write(&buffer[..read]),
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(Error::io(path, e)),
+        }
+    }
+    let hash = hasher.finish();
+    debug!(
+        path = %canonical.display(),
+        elapsed_ms = start.elapsed().as_secs_f64() * 1000.0,
+        "model file hashed"
+    );
+
+    // Only an optimization; the next start hashes again if this fails
+    let partial = sidecar.with_extension(format!("source.{}.tmp", std::process::id()));
+    let written = std::fs::write(&partial, format!("{} {:016x}\n", stamp, hash))
+        .and_then(|()| std::fs::rename(&partial, &sidecar));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&partial);
+        debug!(path = %sidecar.display(), error = %e, "model hash not recorded");
+    }
+    Ok(hash)
+}
+
+/// Size, modification time, inode and change time of a file. The change
+/// time can't be set by tools that preserve timestamps, so replacing the
+/// file always changes the stamp.
+#[cfg(unix)]
+fn file_stamp(metadata: &Metadata) -> String {
+    use std::os::unix::fs::MetadataExt;
+    format!(
+        "{} {} {} {}.{:09}",
+        metadata.len(),
+        modified_nanos(metadata),
+        metadata.ino(),
+        metadata.ctime(),
+        metadata.ctime_nsec()
+    )
+}
+
+/// Size and modification time of a file
+#[cfg(not(unix))]
+fn file_stamp(metadata: &Metadata) -> String {
+    format!("{} {}", metadata.len(), modified_nanos(metadata))
+}
+
+fn modified_nanos(metadata: &Metadata) -> u128 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+/// ONNX Runtime version and commit, e.g. "ORT Build Info: git-branch=..."
+fn runtime_build() -> String {
+    let api = ort::ort();
+    match api.GetBuildInfoString {
+        Some(build_info) => {
+            // SAFETY: returns a static NUL-terminated string owned by ONNX Runtime
+            unsafe { CStr::from_ptr(build_info()) }
+                .to_string_lossy()
+                .into_owned()
+        }
+        None => String::new(),
+    }
+}
+
+/// Create a throwaway session that writes its optimized graph to `output`
+fn save_optimized(
+    env: &Environment,
+    source: &ModelSource,
+    level: sys::GraphOptimizationLevel,
+    output: &Path,
+) -> Result<()> {
+    let error =
+        |message: String| Error::inference(format!("Failed to cache optimized model: {}", message));
+    let output = ort_path(output);
+
+    let mut options: *mut sys::OrtSessionOptions = ptr::null_mut();
+    // SAFETY: every pointer passed below is either created by ONNX Runtime in
+    // this function or outlives the call, and each object is released once.
+    unsafe {
+        ort_call!(CreateSessionOptions(&mut options)).map_err(error)?;
+        let result = (|| {
+            ort_call!(SetSessionGraphOptimizationLevel(options, level))?;
+            ort_call!(SetOptimizedModelFilePath(options, output.as_ptr()))?;
+
+            let mut session: *mut sys::OrtSession = ptr::null_mut();
+            match source {
+                ModelSource::File(path) => {
+                    let path = ort_path(Path::new(path));
+                    ort_call!(CreateSession(
+                        env.ptr(),
+                        path.as_ptr(),
+                        options,
+                        &mut session,
+                    ))?;
+                }
+                ModelSource::Memory(bytes) => {
+                    ort_call!(CreateSessionFromArray(
+                        env.ptr(),
+                        bytes.as_ptr() as *const sys::c_void,
+                        bytes.len() as _,
+                        options,
+                        &mut session,
+                    ))?;
+                }
+            }
+            if !session.is_null() {
+                ort_release!(ReleaseSession(session));
+            }
+            Ok(())
+        })();
+        ort_release!(ReleaseSessionOptions(options));
+        result.map_err(error)
+    }
+}
+
+/// 64-bit FNV-1a, stable across Rust releases unlike `DefaultHasher`
+struct Fnv64(u64);
+
+impl Default for Fnv64 {
+    fn default() -> Self {
+        Fnv64(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv64 {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
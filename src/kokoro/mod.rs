@@ -1,7 +1,39 @@
+#[cfg(feature = "async")]
+mod async_tts;
+mod backend;
+#[cfg(feature = "ort")]
+mod cache;
+mod model;
+mod options;
+#[cfg(feature = "ort")]
+mod ort_api;
+mod pool;
+#[cfg(feature = "ort")]
+mod providers;
+mod report;
+mod runtime;
+mod signature;
 mod tts;
+mod variant;
 mod voice;
 
+#[cfg(feature = "async")]
+#[allow(unused_imports)]
+pub use async_tts::{AsyncKokoroTTS, AudioStream};
+#[allow(unused_imports)]
+pub use backend::Backend;
+pub use options::{CancellationToken, SynthesisOptions, SynthesisProgress};
+#[allow(unused_imports)]
+pub use pool::{KokoroPool, PoolStats, DEFAULT_MAX_QUEUE};
+#[allow(unused_imports)]
+pub use report::{StageTimings, SynthesisReport};
+pub use runtime::KokoroRuntime;
 #[allow(unused_imports)]
 pub use tts::GeneratedAudio;
 pub use tts::{KokoroTTS, TTSConfig};
+pub use variant::ModelVariant;
 pub use voice::{load_voice_style, VoiceStyle};
+
+#[allow(unused_imports)]
+pub use crate::phonemes::{PhonemeConversionError, PhonemeSubstitution};
+pub use crate::text::{Language, PartOfSpeech};
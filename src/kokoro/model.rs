@@ -0,0 +1,19 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+/// Where the ONNX model is read from
+#[derive(Clone)]
+pub(crate) enum ModelSource {
+    File(String),
+    Memory(Arc<Cow<'static, [u8]>>),
+}
+
+impl fmt::Display for ModelSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelSource::File(path) => f.write_str(path),
+            ModelSource::Memory(bytes) => write!(f, "<in-memory model, {} bytes>", bytes.len()),
+        }
+    }
+}
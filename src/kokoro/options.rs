@@ -0,0 +1,88 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Shared flag for aborting a synthesis job from another thread. Clones
+/// refer to the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Progress of a chunked synthesis job, reported after every chunk
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SynthesisProgress {
+    pub chunks_done: usize,
+    pub chunks_total: usize,
+    /// Seconds of audio produced so far
+    pub audio_seconds: f32,
+    pub elapsed: Duration,
+}
+
+type ProgressCallback = Arc<dyn Fn(SynthesisProgress) + Send + Sync>;
+
+/// Options for `KokoroTTS::generate_speech_with_options`. The text is
+/// synthesized one sentence (chunk) at a time; the cancellation tokens are
+/// checked before each chunk and the progress callback runs after each one.
+#[derive(Clone, Default)]
+pub struct SynthesisOptions {
+    cancellation: Vec<CancellationToken>,
+    progress: Option<ProgressCallback>,
+}
+
+impl SynthesisOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop before the next chunk once `token` is cancelled. The audio
+    /// produced so far is returned with `GeneratedAudio::cancelled` set.
+    /// Can be called more than once; any cancelled token stops the job.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation.push(token);
+        self
+    }
+
+    /// Called on the synthesizing thread after every chunk.
+    pub fn with_progress(
+        mut self,
+        callback: impl Fn(SynthesisProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .iter()
+            .any(CancellationToken::is_cancelled)
+    }
+
+    pub(crate) fn report_progress(&self, progress: SynthesisProgress) {
+        if let Some(callback) = &self.progress {
+            callback(progress);
+        }
+    }
+}
+
+impl fmt::Debug for SynthesisOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SynthesisOptions")
+            .field("cancellation", &self.cancellation)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,69 @@
+use ort::sys;
+use std::ffi::CStr;
+use std::path::Path;
+
+/// Call an `OrtApi` function by name, for settings ort's safe wrappers don't
+/// expose. Evaluates to `Err` with ONNX Runtime's message when the function
+/// is missing from the loaded library or returns an error status. Must be
+/// used inside `unsafe`.
+macro_rules! ort_call {
+    ($name:ident($($arg:expr),* $(,)?)) => {
+        match ort::ort().$name {
+            Some(function) => $crate::kokoro::ort_api::check(function($($arg),*)),
+            None => Err(concat!("ONNX Runtime has no ", stringify!($name)).to_string()),
+        }
+    };
+}
+
+/// Release an object created through `ort_call!`. Must be used inside `unsafe`.
+macro_rules! ort_release {
+    ($name:ident($object:expr)) => {
+        if let Some(release) = ort::ort().$name {
+            release($object);
+        }
+    };
+}
+
+pub(crate) use {ort_call, ort_release};
+
+/// Turn a non-null status into its error message, releasing it
+pub(crate) fn check(status: sys::OrtStatusPtr) -> std::result::Result<(), String> {
+    if status.is_null() {
+        return Ok(());
+    }
+    let api = ort::ort();
+    // SAFETY: `status` is a valid status returned by ONNX Runtime and is
+    // released exactly once, after its message has been copied
+    unsafe {
+        let message = match api.GetErrorMessage {
+            Some(get_message) => CStr::from_ptr(get_message(status))
+                .to_string_lossy()
+                .into_owned(),
+            None => "unknown error".to_string(),
+        };
+        if let Some(release) = api.ReleaseStatus {
+            release(status);
+        }
+        Err(message)
+    }
+}
+
+/// NUL-terminated path in ONNX Runtime's native character type
+#[cfg(windows)]
+pub(crate) fn ort_path(path: &Path) -> Vec<sys::ortchar> {
+    use std::os::windows::ffi::OsStrExt;
+    path.as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect()
+}
+
+/// NUL-terminated path in ONNX Runtime's native character type
+#[cfg(not(windows))]
+pub(crate) fn ort_path(path: &Path) -> Vec<sys::ortchar> {
+    path.to_string_lossy()
+        .bytes()
+        .map(|byte| byte as sys::ortchar)
+        .chain(std::iter::once(0))
+        .collect()
+}
@@ -0,0 +1,312 @@
+use super::options::SynthesisOptions;
+use super::tts::{GeneratedAudio, KokoroTTS, TTSConfig};
+use super::voice::VoiceStyle;
+use crate::error::{Error, Result};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Default number of requests allowed to wait for a free session
+pub const DEFAULT_MAX_QUEUE: usize = 64;
+
+/// Snapshot of a `KokoroPool`'s load and latency
+#[derive(Debug, Clone, Default)]
+pub struct PoolStats {
+    pub sessions: usize,
+    /// Sessions currently running a request
+    pub busy: usize,
+    /// Requests currently waiting for a free session
+    pub queued: usize,
+    pub peak_queued: usize,
+    /// Requests that returned audio
+    pub completed: u64,
+    /// Requests whose synthesis returned an error. They are not part of the
+    /// wait and latency figures.
+    pub failed: u64,
+    /// Requests rejected with `Error::QueueFull`
+    pub rejected: u64,
+    /// Mean time completed requests waited for a session
+    pub average_wait: Duration,
+    /// Mean time completed requests held a session (G2P and inference)
+    pub average_latency: Duration,
+    pub max_latency: Duration,
+}
+
+#[derive(Default)]
+struct PoolState {
+    free: Vec<usize>,
+    queued: usize,
+    peak_queued: usize,
+    completed: u64,
+    failed: u64,
+    rejected: u64,
+    total_wait: Duration,
+    total_latency: Duration,
+    max_latency: Duration,
+}
+
+/// A fixed set of `KokoroTTS` sessions built from one `TTSConfig` for
+/// parallel inference. The sessions share one ONNX Runtime environment and
+/// one text front end (tokenizer, dictionaries and voices).
+///
+/// Each `generate_*` call runs on a free session. When all sessions are busy
+/// the call blocks until one is released; once `max_queue` calls are already
+/// waiting, further calls fail fast with `Error::QueueFull` so servers can
+/// shed load instead of piling up requests.
+pub struct KokoroPool {
+    engines: Vec<KokoroTTS>,
+    state: Mutex<PoolState>,
+    available: Condvar,
+    max_queue: usize,
+}
+
+/// A session checked out of the pool; returned (and timed) on drop
+struct Lease<'a> {
+    pool: &'a KokoroPool,
+    index: usize,
+    wait: Duration,
+    acquired: Instant,
+    /// Set once the request returned audio; an error or a panic leaves it
+    /// unset and the request is counted as failed
+    succeeded: bool,
+}
+
+impl Drop for Lease<'_> {
+    fn drop(&mut self) {
+        let latency = self.acquired.elapsed();
+        let mut state = self.pool.lock_state();
+        state.free.push(self.index);
+        if self.succeeded {
+            state.completed += 1;
+            state.total_wait += self.wait;
+            state.total_latency += latency;
+            state.max_latency = state.max_latency.max(latency);
+        } else {
+            state.failed += 1;
+        }
+        drop(state);
+        self.pool.available.notify_one();
+    }
+}
+
+impl KokoroPool {
+    /// Load `sessions` copies of the model described by `config`.
+    pub fn new(config: TTSConfig, sessions: usize) -> Result<Self> {
+        if sessions == 0 {
+            return Err(Error::config("A session pool needs at least one session"));
+        }
+
+        let engines = KokoroTTS::with_shared_sessions(config, sessions)?;
+        let state = PoolState {
+            free: (0..engines.len()).rev().collect(),
+            ..PoolState::default()
+        };
+
+        Ok(KokoroPool {
+            engines,
+            state: Mutex::new(state),
+            available: Condvar::new(),
+            max_queue: DEFAULT_MAX_QUEUE,
+        })
+    }
+
+    /// Maximum number of calls allowed to wait for a session before new ones
+    /// are rejected with `Error::QueueFull`. `0` rejects whenever every
+    /// session is busy.
+    pub fn with_max_queue(mut self, max_queue: usize) -> Self {
+        self.max_queue = max_queue;
+        self
+    }
+
+    pub fn size(&self) -> usize {
+        self.engines.len()
+    }
+
+    /// See `KokoroTTS::execution_provider`; every session uses the same one.
+    pub fn execution_provider(&self) -> &'static str {
+        self.engines[0].execution_provider()
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        let state = self.lock_state();
+        let average = |total: Duration| {
+            if state.completed == 0 {
+                Duration::ZERO
+            } else {
+                total.div_f64(state.completed as f64)
+            }
+        };
+
+        PoolStats {
+            sessions: self.engines.len(),
+            busy: self.engines.len() - state.free.len(),
+            queued: state.queued,
+            peak_queued: state.peak_queued,
+            completed: state.completed,
+            failed: state.failed,
+            rejected: state.rejected,
+            average_wait: average(state.total_wait),
+            average_latency: average(state.total_latency),
+            max_latency: state.max_latency,
+        }
+    }
+
+    /// See `KokoroTTS::phonemize`. Runs on the shared front end without
+    /// taking a session.
+    pub fn phonemize(&self, text: &str) -> Result<String> {
+        self.engines[0].phonemize(text)
+    }
+
+    /// See `KokoroTTS::tokenize`. Runs on the shared front end without
+    /// taking a session.
+    pub fn tokenize(&self, text: &str) -> Result<Vec<i64>> {
+        self.engines[0].tokenize(text)
+    }
+
+    pub fn decode(&self, ids: &[i64]) -> String {
+        self.engines[0].decode(ids)
+    }
+
+    pub fn generate_speech(
+        &self,
+        text: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        self.dispatch(|tts| tts.generate_speech(text, voice_style, speed))
+    }
+
+    pub fn generate_speech_with_options(
+        &self,
+        text: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+        options: &SynthesisOptions,
+    ) -> Result<GeneratedAudio> {
+        self.dispatch(|tts| tts.generate_speech_with_options(text, voice_style, speed, options))
+    }
+
+    pub fn generate_speech_from_phonemes(
+        &self,
+        phonemes: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        self.dispatch(|tts| tts.generate_speech_from_phonemes(phonemes, voice_style, speed))
+    }
+
+    pub fn generate_speech_from_ipa(
+        &self,
+        ipa: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        self.dispatch(|tts| tts.generate_speech_from_ipa(ipa, voice_style, speed))
+    }
+
+    pub fn generate_speech_from_arpabet(
+        &self,
+        arpabet: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        self.dispatch(|tts| tts.generate_speech_from_arpabet(arpabet, voice_style, speed))
+    }
+
+    pub fn generate_speech_from_xsampa(
+        &self,
+        xsampa: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        self.dispatch(|tts| tts.generate_speech_from_xsampa(xsampa, voice_style, speed))
+    }
+
+    pub fn generate_speech_japanese(
+        &self,
+        text: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        self.dispatch(|tts| tts.generate_speech_japanese(text, voice_style, speed))
+    }
+
+    pub fn generate_from_tokens(
+        &self,
+        tokens: &[i64],
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        self.dispatch(|tts| tts.generate_from_tokens(tokens, voice_style, speed))
+    }
+
+    pub fn generate_batch(
+        &self,
+        items: &[(&str, &VoiceStyle, f32)],
+    ) -> Result<Vec<GeneratedAudio>> {
+        self.dispatch(|tts| tts.generate_batch(items))
+    }
+
+    pub fn speak(&self, text: &str, voice_style: &VoiceStyle) -> Result<GeneratedAudio> {
+        self.generate_speech(text, voice_style, 1.0)
+    }
+
+    /// Run `f` on a free session, waiting for one if necessary.
+    fn dispatch<T>(&self, f: impl FnOnce(&KokoroTTS) -> Result<T>) -> Result<T> {
+        let mut lease = self.acquire()?;
+        let result = f(&self.engines[lease.index]);
+        lease.succeeded = result.is_ok();
+        result
+    }
+
+    fn acquire(&self) -> Result<Lease<'_>> {
+        let requested = Instant::now();
+        let mut state = self.lock_state();
+
+        let mut waiting = false;
+        let index = loop {
+            if let Some(index) = state.free.pop() {
+                break index;
+            }
+
+            if !waiting {
+                if state.queued >= self.max_queue {
+                    state.rejected += 1;
+                    warn!(
+                        queued = state.queued,
+                        limit = self.max_queue,
+                        "session pool queue full"
+                    );
+                    return Err(Error::QueueFull {
+                        limit: self.max_queue,
+                    });
+                }
+                waiting = true;
+                state.queued += 1;
+                state.peak_queued = state.peak_queued.max(state.queued);
+                debug!(queued = state.queued, "waiting for a free session");
+            }
+
+            state = self
+                .available
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        };
+        if waiting {
+            state.queued -= 1;
+        }
+
+        Ok(Lease {
+            pool: self,
+            index,
+            wait: requested.elapsed(),
+            acquired: Instant::now(),
+            succeeded: false,
+        })
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, PoolState> {
+        // Leases are returned from Drop, even during a panic; keep going
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,393 @@
+#[cfg(any(feature = "onednn", feature = "coreml", feature = "directml"))]
+use super::ort_api::check;
+#[cfg(any(
+    feature = "cuda",
+    feature = "tensorrt",
+    feature = "openvino",
+    feature = "rocm"
+))]
+use super::ort_api::ort_call;
+#[cfg(any(feature = "cuda", feature = "tensorrt"))]
+use super::ort_api::ort_release;
+use crate::error::{Error, Result};
+#[cfg(any(feature = "cuda", feature = "rocm"))]
+use ort::execution_providers::ArenaExtendStrategy;
+#[cfg(feature = "cuda")]
+use ort::execution_providers::CUDAExecutionProviderCuDNNConvAlgoSearch;
+use ort::{sys, ExecutionProvider};
+#[cfg(any(feature = "cuda", feature = "tensorrt", feature = "openvino"))]
+use std::ffi::CString;
+#[cfg(any(feature = "cuda", feature = "tensorrt"))]
+use std::os::raw::c_char;
+#[cfg(any(feature = "onednn", feature = "directml"))]
+use std::os::raw::c_int;
+#[cfg(any(
+    feature = "cuda",
+    feature = "tensorrt",
+    feature = "openvino",
+    feature = "rocm"
+))]
+use std::ptr;
+use tracing::{debug, info, warn};
+
+/// Name ONNX Runtime uses for its always-available CPU provider
+pub(crate) const CPU_PROVIDER: &str = "CPUExecutionProvider";
+
+/// Whether ort was built with support for `provider`. Providers are enabled
+/// through this crate's features of the same name (`cuda`, `coreml`, ...).
+#[allow(clippy::match_like_matches_macro)]
+fn is_compiled(provider: &ExecutionProvider) -> bool {
+    match provider {
+        ExecutionProvider::CPU(_) => true,
+        ExecutionProvider::CUDA(_) => cfg!(feature = "cuda"),
+        ExecutionProvider::TensorRT(_) => cfg!(feature = "tensorrt"),
+        ExecutionProvider::OpenVINO(_) => cfg!(feature = "openvino"),
+        ExecutionProvider::OneDNN(_) => cfg!(feature = "onednn"),
+        ExecutionProvider::CoreML(_) => cfg!(feature = "coreml"),
+        ExecutionProvider::DirectML(_) => cfg!(feature = "directml"),
+        ExecutionProvider::ROCm(_) => cfg!(feature = "rocm"),
+        _ => false,
+    }
+}
+
+/// Register the first provider in `providers` that this build and the
+/// loaded ONNX Runtime library accept, trying them in order, and return the
+/// name of the one that registered. A provider that fails to register (a
+/// missing CUDA library, no device, ...) is skipped like one that isn't
+/// available. When none registers the session runs on the CPU provider.
+///
+/// With `strict`, running on CPU when no listed provider is usable is an
+/// error instead of a warning.
+///
+/// # Safety
+///
+/// `options` must be a live `OrtSessionOptions`.
+pub(crate) unsafe fn register_provider(
+    options: *mut sys::OrtSessionOptions,
+    providers: &[ExecutionProvider],
+    strict: bool,
+) -> Result<&'static str> {
+    let mut skipped = Vec::new();
+    for provider in providers {
+        if !is_compiled(provider) {
+            debug!(
+                provider = provider.as_str(),
+                "provider support not compiled in"
+            );
+            skipped.push(format!("{} (not compiled in)", provider.as_str()));
+        } else if !provider.is_available() {
+            debug!(
+                provider = provider.as_str(),
+                "provider missing from ONNX Runtime library"
+            );
+            skipped.push(format!(
+                "{} (not in ONNX Runtime library)",
+                provider.as_str()
+            ));
+        } else {
+            match append_provider(options, provider) {
+                Ok(()) => {
+                    info!(
+                        provider = provider.as_str(),
+                        "execution provider registered"
+                    );
+                    return Ok(provider.as_str());
+                }
+                Err(e) => {
+                    warn!(
+                        provider = provider.as_str(),
+                        error = %e,
+                        "execution provider failed to register"
+                    );
+                    skipped.push(format!("{} ({})", provider.as_str(), e));
+                }
+            }
+        }
+    }
+
+    if strict && !skipped.is_empty() {
+        return Err(Error::config(format!(
+            "No requested execution provider is usable: {}",
+            skipped.join(", ")
+        )));
+    }
+    if !skipped.is_empty() {
+        warn!(
+            skipped = %skipped.join(", "),
+            "no requested execution provider is usable, falling back to CPU"
+        );
+    }
+    Ok(CPU_PROVIDER)
+}
+
+#[cfg(all(feature = "onednn", not(target_arch = "x86")))]
+extern "C" {
+    fn OrtSessionOptionsAppendExecutionProvider_Dnnl(
+        options: *mut sys::OrtSessionOptions,
+        use_arena: c_int,
+    ) -> sys::OrtStatusPtr;
+}
+
+#[cfg(all(feature = "coreml", not(target_arch = "x86")))]
+extern "C" {
+    fn OrtSessionOptionsAppendExecutionProvider_CoreML(
+        options: *mut sys::OrtSessionOptions,
+        flags: u32,
+    ) -> sys::OrtStatusPtr;
+}
+
+#[cfg(all(feature = "directml", not(target_arch = "x86")))]
+extern "C" {
+    fn OrtSessionOptionsAppendExecutionProvider_DML(
+        options: *mut sys::OrtSessionOptions,
+        device_id: c_int,
+    ) -> sys::OrtStatusPtr;
+}
+
+/// Register `provider` on `options`, returning ONNX Runtime's error message
+/// if it refuses. Unlike ort's `SessionBuilder`, which logs registration
+/// errors and carries on, this lets callers know where the session will
+/// actually run.
+///
+/// # Safety
+///
+/// `options` must be a live `OrtSessionOptions`.
+unsafe fn append_provider(
+    // Unused when no hardware provider feature is enabled
+    #[allow(unused_variables)] options: *mut sys::OrtSessionOptions,
+    provider: &ExecutionProvider,
+) -> std::result::Result<(), String> {
+    match provider {
+        // Always present; its arena is a session option, set from
+        // `TTSConfig::with_memory_arena` whichever provider registers
+        ExecutionProvider::CPU(_) => Ok(()),
+        #[cfg(feature = "cuda")]
+        ExecutionProvider::CUDA(cuda) => {
+            let mut keys = ProviderKeys::default();
+            keys.push("device_id", cuda.device_id);
+            keys.push("gpu_mem_limit", cuda.gpu_mem_limit);
+            keys.push(
+                "arena_extend_strategy",
+                arena_extend_strategy(&cuda.arena_extend_strategy),
+            );
+            keys.push(
+                "cudnn_conv_algo_search",
+                match cuda.cudnn_conv_algo_search {
+                    CUDAExecutionProviderCuDNNConvAlgoSearch::Exhaustive => "EXHAUSTIVE",
+                    CUDAExecutionProviderCuDNNConvAlgoSearch::Heuristic => "HEURISTIC",
+                    CUDAExecutionProviderCuDNNConvAlgoSearch::Default => "DEFAULT",
+                },
+            );
+            keys.push_flag("do_copy_in_default_stream", cuda.do_copy_in_default_stream);
+            keys.push_flag(
+                "cudnn_conv_use_max_workspace",
+                cuda.cudnn_conv_use_max_workspace,
+            );
+            keys.push_flag("cudnn_conv1d_pad_to_nc1d", cuda.cudnn_conv1d_pad_to_nc1d);
+            keys.push_flag("enable_cuda_graph", cuda.enable_cuda_graph);
+            keys.push_flag(
+                "enable_skip_layer_norm_strict_mode",
+                cuda.enable_skip_layer_norm_strict_mode,
+            );
+            let (names, values) = keys.pointers();
+
+            let mut cuda_options: *mut sys::OrtCUDAProviderOptionsV2 = ptr::null_mut();
+            ort_call!(CreateCUDAProviderOptions(&mut cuda_options))?;
+            let result = ort_call!(UpdateCUDAProviderOptions(
+                cuda_options,
+                names.as_ptr(),
+                values.as_ptr(),
+                names.len() as _,
+            ))
+            .and_then(|()| {
+                ort_call!(SessionOptionsAppendExecutionProvider_CUDA_V2(
+                    options,
+                    cuda_options
+                ))
+            });
+            ort_release!(ReleaseCUDAProviderOptions(cuda_options));
+            result
+        }
+        #[cfg(feature = "tensorrt")]
+        ExecutionProvider::TensorRT(trt) => {
+            let mut keys = ProviderKeys::default();
+            keys.push("device_id", trt.device_id);
+            keys.push("trt_max_workspace_size", trt.max_workspace_size);
+            keys.push("trt_max_partition_iterations", trt.max_partition_iterations);
+            keys.push("trt_min_subgraph_size", trt.min_subgraph_size);
+            keys.push_flag("trt_fp16_enable", trt.fp16_enable);
+            keys.push_flag("trt_int8_enable", trt.int8_enable);
+            keys.push(
+                "trt_int8_calibration_table_name",
+                &trt.int8_calibration_table_name,
+            );
+            keys.push_flag("trt_dla_enable", trt.dla_enable);
+            keys.push("trt_dla_core", trt.dla_core);
+            keys.push_flag("trt_engine_cache_enable", trt.engine_cache_enable);
+            keys.push("trt_engine_cache_path", &trt.engine_cache_path);
+            keys.push_flag("trt_dump_subgraphs", trt.dump_subgraphs);
+            keys.push_flag(
+                "trt_force_sequential_engine_build",
+                trt.force_sequential_engine_build,
+            );
+            keys.push_flag(
+                "trt_context_memory_sharing_enable",
+                trt.enable_context_memory_sharing,
+            );
+            keys.push_flag("trt_layer_norm_fp32_fallback", trt.layer_norm_fp32_fallback);
+            keys.push_flag("trt_timing_cache_enable", trt.timing_cache_enable);
+            keys.push_flag("trt_force_timing_cache_match", trt.force_timing_cache);
+            keys.push_flag("trt_detailed_build_log", trt.detailed_build_log);
+            keys.push_flag("trt_build_heuristics_enable", trt.enable_build_heuristics);
+            keys.push_flag("trt_sparsity_enable", trt.enable_sparsity);
+            keys.push(
+                "trt_builder_optimization_level",
+                trt.builder_optimization_level,
+            );
+            keys.push("trt_auxiliary_streams", trt.auxiliary_streams);
+            keys.push("trt_tactic_sources", &trt.tactic_sources);
+            keys.push("trt_extra_plugin_lib_paths", &trt.extra_plugin_lib_paths);
+            keys.push("trt_profile_min_shapes", &trt.profile_min_shapes);
+            keys.push("trt_profile_max_shapes", &trt.profile_max_shapes);
+            keys.push("trt_profile_opt_shapes", &trt.profile_opt_shapes);
+            let (names, values) = keys.pointers();
+
+            let mut trt_options: *mut sys::OrtTensorRTProviderOptionsV2 = ptr::null_mut();
+            ort_call!(CreateTensorRTProviderOptions(&mut trt_options))?;
+            let result = ort_call!(UpdateTensorRTProviderOptions(
+                trt_options,
+                names.as_ptr(),
+                values.as_ptr(),
+                names.len() as _,
+            ))
+            .and_then(|()| {
+                ort_call!(SessionOptionsAppendExecutionProvider_TensorRT_V2(
+                    options,
+                    trt_options
+                ))
+            });
+            ort_release!(ReleaseTensorRTProviderOptions(trt_options));
+            result
+        }
+        #[cfg(feature = "openvino")]
+        ExecutionProvider::OpenVINO(openvino) => {
+            let c_string = |value: &Option<String>| {
+                value
+                    .as_deref()
+                    .map(CString::new)
+                    .transpose()
+                    .map_err(|_| "OpenVINO option contains a NUL byte".to_string())
+            };
+            let device_type = c_string(&openvino.device_type)?;
+            let device_id = c_string(&openvino.device_id)?;
+            let cache_dir = c_string(&openvino.cache_dir)?;
+            let as_ptr =
+                |value: &Option<CString>| value.as_ref().map_or(ptr::null(), |v| v.as_ptr());
+            let openvino_options = sys::OrtOpenVINOProviderOptions {
+                device_type: as_ptr(&device_type),
+                enable_vpu_fast_compile: openvino.enable_vpu_fast_compile.into(),
+                device_id: as_ptr(&device_id),
+                num_of_threads: openvino.num_threads,
+                cache_dir: as_ptr(&cache_dir),
+                context: openvino.context,
+                enable_opencl_throttling: openvino.enable_opencl_throttling.into(),
+                enable_dynamic_shapes: openvino.enable_dynamic_shapes.into(),
+            };
+            ort_call!(SessionOptionsAppendExecutionProvider_OpenVINO(
+                options,
+                &openvino_options
+            ))
+        }
+        #[cfg(feature = "rocm")]
+        ExecutionProvider::ROCm(rocm) => {
+            let rocm_options = sys::OrtROCMProviderOptions {
+                device_id: rocm.device_id,
+                miopen_conv_exhaustive_search: rocm.miopen_conv_exhaustive_search.into(),
+                gpu_mem_limit: rocm.gpu_mem_limit,
+                arena_extend_strategy: match rocm.arena_extend_strategy {
+                    ArenaExtendStrategy::NextPowerOfTwo => 0,
+                    ArenaExtendStrategy::SameAsRequested => 1,
+                },
+                do_copy_in_default_stream: rocm.do_copy_in_default_stream.into(),
+                has_user_compute_stream: rocm.user_compute_stream.is_some().into(),
+                user_compute_stream: rocm.user_compute_stream.unwrap_or(ptr::null_mut()),
+                default_memory_arena_cfg: rocm.default_memory_arena_cfg.unwrap_or(ptr::null_mut()),
+                tunable_op_enable: rocm.tunable_op_enable.into(),
+                tunable_op_tuning_enable: rocm.tunable_op_tuning_enable.into(),
+                tunable_op_max_tuning_duration_ms: rocm.tunable_op_max_tuning_duration_ms,
+            };
+            ort_call!(SessionOptionsAppendExecutionProvider_ROCM(
+                options,
+                &rocm_options
+            ))
+        }
+        #[cfg(all(feature = "onednn", not(target_arch = "x86")))]
+        ExecutionProvider::OneDNN(onednn) => check(OrtSessionOptionsAppendExecutionProvider_Dnnl(
+            options,
+            onednn.use_arena.into(),
+        )),
+        #[cfg(all(feature = "coreml", not(target_arch = "x86")))]
+        ExecutionProvider::CoreML(coreml) => {
+            let mut flags = 0;
+            if coreml.use_cpu_only {
+                flags |= 0x001;
+            }
+            if coreml.enable_on_subgraph {
+                flags |= 0x002;
+            }
+            if coreml.only_enable_device_with_ane {
+                flags |= 0x004;
+            }
+            check(OrtSessionOptionsAppendExecutionProvider_CoreML(
+                options, flags,
+            ))
+        }
+        #[cfg(all(feature = "directml", not(target_arch = "x86")))]
+        ExecutionProvider::DirectML(directml) => check(
+            OrtSessionOptionsAppendExecutionProvider_DML(options, directml.device_id as c_int),
+        ),
+        provider => Err(format!("{} is not supported", provider.as_str())),
+    }
+}
+
+/// Provider option keys and values as C strings; empty values are left out
+/// so ONNX Runtime keeps its defaults
+#[cfg(any(feature = "cuda", feature = "tensorrt"))]
+#[derive(Default)]
+struct ProviderKeys {
+    names: Vec<CString>,
+    values: Vec<CString>,
+}
+
+#[cfg(any(feature = "cuda", feature = "tensorrt"))]
+impl ProviderKeys {
+    fn push(&mut self, name: &str, value: impl ToString) {
+        let value = value.to_string();
+        if let (false, Ok(name), Ok(value)) =
+            (value.is_empty(), CString::new(name), CString::new(value))
+        {
+            self.names.push(name);
+            self.values.push(value);
+        }
+    }
+
+    fn push_flag(&mut self, name: &str, enabled: bool) {
+        self.push(name, u8::from(enabled));
+    }
+
+    /// Pointers into the strings, valid while `self` is alive
+    fn pointers(&self) -> (Vec<*const c_char>, Vec<*const c_char>) {
+        (
+            self.names.iter().map(|name| name.as_ptr()).collect(),
+            self.values.iter().map(|value| value.as_ptr()).collect(),
+        )
+    }
+}
+
+#[cfg(feature = "cuda")]
+fn arena_extend_strategy(strategy: &ArenaExtendStrategy) -> &'static str {
+    match strategy {
+        ArenaExtendStrategy::NextPowerOfTwo => "kNextPowerOfTwo",
+        ArenaExtendStrategy::SameAsRequested => "kSameAsRequested",
+    }
+}
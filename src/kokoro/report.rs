@@ -0,0 +1,71 @@
+use crate::phonemes::PhonemeSubstitution;
+use serde_json::json;
+use std::time::Duration;
+
+/// Wall-clock time spent in each stage of one generation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StageTimings {
+    /// Text to Misaki phonemes (zero for phoneme and token input)
+    pub g2p: Duration,
+    /// Phonemes to token ids
+    pub tokenization: Duration,
+    /// ONNX model run
+    pub inference: Duration,
+}
+
+/// Everything that went into one model run, enabled with
+/// `TTSConfig::with_synthesis_report`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SynthesisReport {
+    /// Raw espeak-ng IPA, when espeak phonemized the input
+    pub espeak_ipa: Option<String>,
+    /// Misaki phonemes that were tokenized
+    pub phonemes: String,
+    /// Token ids passed to the model, including BOS/EOS
+    pub token_ids: Vec<i64>,
+    pub truncated: bool,
+    /// Tokens cut off to fit `TTSConfig::max_length`
+    pub dropped_tokens: usize,
+    /// Out-of-vocabulary symbols that were replaced or dropped
+    pub unknown_symbols: Vec<PhonemeSubstitution>,
+    /// Row of the voice style matrix used for this token count
+    pub style_index: usize,
+    pub timings: StageTimings,
+}
+
+impl SynthesisReport {
+    /// The report as a JSON value. Timings are in milliseconds.
+    pub fn to_json(&self) -> serde_json::Value {
+        let unknown_symbols: Vec<serde_json::Value> = self
+            .unknown_symbols
+            .iter()
+            .map(|s| {
+                json!({
+                    "position": s.position,
+                    "original": s.original.to_string(),
+                    "replacement": s.replacement.map(|c| c.to_string()),
+                })
+            })
+            .collect();
+
+        json!({
+            "espeak_ipa": self.espeak_ipa,
+            "phonemes": self.phonemes,
+            "token_ids": self.token_ids,
+            "truncated": self.truncated,
+            "dropped_tokens": self.dropped_tokens,
+            "unknown_symbols": unknown_symbols,
+            "style_index": self.style_index,
+            "timings_ms": {
+                "g2p": self.timings.g2p.as_secs_f64() * 1000.0,
+                "tokenization": self.timings.tokenization.as_secs_f64() * 1000.0,
+                "inference": self.timings.inference.as_secs_f64() * 1000.0,
+            },
+        })
+    }
+
+    /// The report as pretty-printed JSON.
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string_pretty(&self.to_json()).unwrap_or_default()
+    }
+}
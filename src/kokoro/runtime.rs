@@ -0,0 +1,134 @@
+use super::backend::BackendEnvironment;
+use super::tts::{KokoroTTS, TTSConfig};
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Instant;
+use tracing::info;
+
+struct LoadedModel {
+    /// Kept to load the model again on `reload`
+    config: TTSConfig,
+    tts: Arc<KokoroTTS>,
+}
+
+/// One inference environment (with ONNX Runtime, a single `Environment`)
+/// shared by any number of named models, e.g. the full, quantized and legacy
+/// exports side by side.
+///
+/// Models are handed out as `Arc<KokoroTTS>`. Unloading or reloading a model
+/// only changes what the runtime hands out next: existing handles keep the
+/// model they were given, which is released when the last one is dropped.
+pub struct KokoroRuntime {
+    env: BackendEnvironment,
+    models: RwLock<HashMap<String, LoadedModel>>,
+}
+
+impl KokoroRuntime {
+    pub fn new() -> Result<Self> {
+        Ok(KokoroRuntime {
+            env: BackendEnvironment::new()?,
+            models: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Load a model under `name`, replacing any model already registered
+    /// under it. Loading happens without blocking other models.
+    pub fn load(&self, name: &str, config: TTSConfig) -> Result<Arc<KokoroTTS>> {
+        let start = Instant::now();
+        let tts = self.load_engine(config.clone())?;
+        let replaced = self
+            .write_models()
+            .insert(
+                name.to_string(),
+                LoadedModel {
+                    config,
+                    tts: Arc::clone(&tts),
+                },
+            )
+            .is_some();
+        info!(
+            name,
+            replaced,
+            elapsed_ms = start.elapsed().as_secs_f64() * 1000.0,
+            "runtime model loaded"
+        );
+        Ok(tts)
+    }
+
+    /// Load the model registered under `name` again from its config, e.g.
+    /// after the file on disk was replaced. Fails with an unknown-model error
+    /// if `name` is unloaded before the new model is ready.
+    pub fn reload(&self, name: &str) -> Result<Arc<KokoroTTS>> {
+        let config = match self.read_models().get(name) {
+            Some(model) => model.config.clone(),
+            None => return Err(unknown_model(name)),
+        };
+        let start = Instant::now();
+        let tts = self.load_engine(config.clone())?;
+        // The model may have been unloaded while this one was loading;
+        // don't bring it back
+        match self.write_models().get_mut(name) {
+            Some(model) => {
+                *model = LoadedModel {
+                    config,
+                    tts: Arc::clone(&tts),
+                }
+            }
+            None => return Err(unknown_model(name)),
+        }
+        info!(
+            name,
+            elapsed_ms = start.elapsed().as_secs_f64() * 1000.0,
+            "runtime model reloaded"
+        );
+        Ok(tts)
+    }
+
+    /// Remove `name` from the registry. Returns false if no such model is
+    /// loaded.
+    pub fn unload(&self, name: &str) -> bool {
+        let removed = self.write_models().remove(name).is_some();
+        if removed {
+            info!(name, "runtime model unloaded");
+        }
+        removed
+    }
+
+    /// Handle to the model registered under `name`
+    pub fn get(&self, name: &str) -> Result<Arc<KokoroTTS>> {
+        self.read_models()
+            .get(name)
+            .map(|model| Arc::clone(&model.tts))
+            .ok_or_else(|| unknown_model(name))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.read_models().contains_key(name)
+    }
+
+    /// Names of the loaded models, sorted
+    pub fn models(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.read_models().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    fn load_engine(&self, config: TTSConfig) -> Result<Arc<KokoroTTS>> {
+        let mut engines = KokoroTTS::with_environment(&self.env, config, 1)?;
+        Ok(Arc::new(engines.remove(0)))
+    }
+
+    // A panic while holding the lock leaves the map itself consistent
+    fn read_models(&self) -> RwLockReadGuard<'_, HashMap<String, LoadedModel>> {
+        self.models.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write_models(&self) -> RwLockWriteGuard<'_, HashMap<String, LoadedModel>> {
+        self.models.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn unknown_model(name: &str) -> Error {
+    Error::config(format!("No model named '{}' is loaded", name))
+}
@@ -0,0 +1,279 @@
+use super::backend::{ElementType, InferenceBackend, TensorData, TensorInfo};
+use crate::error::{Error, Result};
+use half::f16;
+use ndarray::{Array0, Array1, Array2, ArrayD};
+use std::fmt;
+
+/// Style vector width of the published Kokoro voices
+pub(crate) const DEFAULT_STYLE_WIDTH: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputRole {
+    Tokens,
+    Style,
+    Speed,
+}
+
+#[derive(Debug, Clone)]
+struct BoundInput {
+    role: InputRole,
+    name: String,
+    dtype: ElementType,
+    /// Number of dimensions the model expects
+    rank: usize,
+}
+
+/// How a Kokoro export names, types and shapes its inputs and outputs.
+///
+/// v0.19 exports take `tokens` and return `audio`; v1.0 exports take
+/// `input_ids` and return `waveform`. Inputs are matched by name (falling
+/// back to the `ids, style, speed` order when no name is recognized) and fed
+/// in the dtype and rank the model declares. Half precision (fp16) exports
+/// are converted to and from f32 at the boundary.
+#[derive(Debug, Clone)]
+pub(crate) struct ModelSignature {
+    /// In model input order
+    inputs: Vec<BoundInput>,
+    pub(crate) style_width: usize,
+    waveform_output: usize,
+    /// Per-token durations, when the export provides them
+    pub(crate) duration_output: Option<usize>,
+    /// Every input has a dynamic leading (batch) dimension
+    pub(crate) dynamic_batch: bool,
+}
+
+impl ModelSignature {
+    pub(crate) fn from_backend(backend: &dyn InferenceBackend) -> Result<Self> {
+        let (model_inputs, model_outputs) = (backend.inputs(), backend.outputs());
+        let mut roles: Vec<Option<InputRole>> = model_inputs
+            .iter()
+            .map(|input| input_role(&input.name))
+            .collect();
+        // Unnamed exports: assume the reference positional layout
+        if roles.len() == 3 && roles.iter().all(Option::is_none) {
+            roles = vec![
+                Some(InputRole::Tokens),
+                Some(InputRole::Style),
+                Some(InputRole::Speed),
+            ];
+        }
+
+        let reject = |reason: String| unsupported(backend, reason);
+        let mut inputs = Vec::with_capacity(model_inputs.len());
+        for (input, role) in model_inputs.iter().zip(roles) {
+            let Some(role) = role else {
+                return Err(reject(format!("unknown input '{}'", input.name)));
+            };
+            if inputs.iter().any(|bound: &BoundInput| bound.role == role) {
+                return Err(reject(format!("more than one {:?} input", role)));
+            }
+
+            let rank = input.dims.len();
+            let supported = match role {
+                InputRole::Tokens => {
+                    matches!(input.dtype, ElementType::Int64 | ElementType::Int32)
+                        && matches!(rank, 1 | 2)
+                }
+                InputRole::Style => is_float(input.dtype) && matches!(rank, 1 | 2),
+                InputRole::Speed => input.dtype != ElementType::Other && matches!(rank, 0 | 1),
+            };
+            if !supported {
+                return Err(reject(format!(
+                    "input '{}' has type {:?} with {} dimensions",
+                    input.name, input.dtype, rank
+                )));
+            }
+
+            inputs.push(BoundInput {
+                role,
+                name: input.name.clone(),
+                dtype: input.dtype,
+                rank,
+            });
+        }
+
+        for role in [InputRole::Tokens, InputRole::Style, InputRole::Speed] {
+            if !inputs.iter().any(|bound| bound.role == role) {
+                return Err(reject(format!("no {:?} input", role)));
+            }
+        }
+
+        let style_width = model_inputs
+            .iter()
+            .zip(&inputs)
+            .find(|(_, bound)| bound.role == InputRole::Style)
+            .and_then(|(input, _)| input.dims.last().copied().flatten())
+            .map_or(DEFAULT_STYLE_WIDTH, |width| width as usize);
+
+        let waveform_output = model_outputs
+            .iter()
+            .position(|output| {
+                let name = output.name.to_lowercase();
+                name.contains("wav") || name.contains("audio")
+            })
+            .or_else(|| {
+                model_outputs
+                    .iter()
+                    .position(|output| is_float(output.dtype))
+            })
+            .ok_or_else(|| reject("no audio output".to_string()))?;
+        if !is_float(model_outputs[waveform_output].dtype) {
+            return Err(reject(format!(
+                "audio output '{}' has type {:?}",
+                model_outputs[waveform_output].name, model_outputs[waveform_output].dtype
+            )));
+        }
+
+        let duration_output = model_outputs
+            .iter()
+            .position(|output| output.name.to_lowercase().contains("dur"));
+
+        let dynamic_batch = model_inputs
+            .iter()
+            .all(|input| matches!(input.dims.first(), Some(None)));
+
+        Ok(ModelSignature {
+            inputs,
+            style_width,
+            waveform_output,
+            duration_output,
+            dynamic_batch,
+        })
+    }
+
+    /// Arrange `[batch, tokens]` ids, `[batch, style_width]` styles and
+    /// `[batch]` speeds in the model's input order, dtypes and ranks.
+    pub(crate) fn bind(
+        &self,
+        tokens: Array2<i64>,
+        style: Array2<f32>,
+        speed: Array1<f32>,
+    ) -> Result<Vec<TensorData>> {
+        let batch_size = tokens.nrows();
+        let unbatched = |name: &str| {
+            Error::inference(format!(
+                "Input '{}' has no batch dimension; cannot run {} utterances at once",
+                name, batch_size
+            ))
+        };
+
+        self.inputs
+            .iter()
+            .map(|input| {
+                let tensor = match input.role {
+                    InputRole::Tokens => {
+                        let tokens = match input.rank {
+                            1 if batch_size != 1 => return Err(unbatched(&input.name)),
+                            1 => tokens.row(0).to_owned().into_dyn(),
+                            _ => tokens.clone().into_dyn(),
+                        };
+                        match input.dtype {
+                            ElementType::Int32 => TensorData::Int32(tokens.mapv(|id| id as i32)),
+                            _ => TensorData::Int64(tokens),
+                        }
+                    }
+                    InputRole::Style => {
+                        let style = match input.rank {
+                            1 if batch_size != 1 => return Err(unbatched(&input.name)),
+                            1 => style.row(0).to_owned().into_dyn(),
+                            _ => style.clone().into_dyn(),
+                        };
+                        match input.dtype {
+                            ElementType::Float16 => TensorData::Float16(style.mapv(f16::from_f32)),
+                            _ => TensorData::Float32(style),
+                        }
+                    }
+                    InputRole::Speed => {
+                        let speed = match input.rank {
+                            0 if batch_size != 1 => return Err(unbatched(&input.name)),
+                            0 => Array0::from_elem((), speed[0]).into_dyn(),
+                            _ => speed.clone().into_dyn(),
+                        };
+                        let integral =
+                            matches!(input.dtype, ElementType::Int32 | ElementType::Int64);
+                        if let Some(s) = speed.iter().find(|s| integral && s.fract() != 0.0) {
+                            return Err(Error::config(format!(
+                                "Model input '{}' takes a whole-number speed, got {}",
+                                input.name, s
+                            )));
+                        }
+                        match input.dtype {
+                            ElementType::Float16 => TensorData::Float16(speed.mapv(f16::from_f32)),
+                            ElementType::Float64 => TensorData::Float64(speed.mapv(f64::from)),
+                            ElementType::Int32 => TensorData::Int32(speed.mapv(|s| s as i32)),
+                            ElementType::Int64 => TensorData::Int64(speed.mapv(|s| s as i64)),
+                            _ => TensorData::Float32(speed),
+                        }
+                    }
+                };
+                Ok(tensor)
+            })
+            .collect()
+    }
+
+    /// The audio output as f32 samples
+    pub(crate) fn waveform(&self, outputs: &[TensorData]) -> Result<ArrayD<f32>> {
+        let output = outputs
+            .get(self.waveform_output)
+            .ok_or_else(|| Error::output("Model returned no audio output"))?;
+        output.to_f32().ok_or_else(|| {
+            Error::output(format!("Audio output has type {:?}", output.element_type()))
+        })
+    }
+}
+
+impl fmt::Display for ModelSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inputs: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|input| format!("{}: {:?}/{:?}", input.name, input.role, input.dtype))
+            .collect();
+        write!(
+            f,
+            "inputs [{}], style width {}, batching {}",
+            inputs.join(", "),
+            self.style_width,
+            if self.dynamic_batch {
+                "dynamic"
+            } else {
+                "fixed"
+            }
+        )
+    }
+}
+
+fn is_float(dtype: ElementType) -> bool {
+    matches!(dtype, ElementType::Float16 | ElementType::Float32)
+}
+
+fn input_role(name: &str) -> Option<InputRole> {
+    let name = name.to_lowercase();
+    if name.contains("style") || name == "ref_s" {
+        Some(InputRole::Style)
+    } else if name.contains("speed") {
+        Some(InputRole::Speed)
+    } else if name.contains("token") || name.contains("ids") {
+        Some(InputRole::Tokens)
+    } else {
+        None
+    }
+}
+
+fn unsupported(backend: &dyn InferenceBackend, reason: String) -> Error {
+    let describe =
+        |tensor: &TensorInfo| format!("{} {:?} {:?}", tensor.name, tensor.dtype, tensor.dims);
+    let inputs: Vec<String> = backend.inputs().iter().map(describe).collect();
+    let outputs: Vec<String> = backend
+        .outputs()
+        .iter()
+        .map(|output| format!("{} {:?}", output.name, output.dtype))
+        .collect();
+
+    Error::unsupported_model(format!(
+        "{} (inputs: {}; outputs: {})",
+        reason,
+        inputs.join(", "),
+        outputs.join(", ")
+    ))
+}
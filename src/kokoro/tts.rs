@@ -1,33 +1,145 @@
-use super::voice::VoiceStyle;
-use crate::espeak::EspeakIpaTokenizer;
-use ndarray::{Array1, Array2, CowArray, IxDyn};
-use ort::{Environment, ExecutionProvider, GraphOptimizationLevel, Session, SessionBuilder, Value};
-use std::error::Error;
+#[cfg(feature = "ort")]
+use super::backend::{copy_level, OrtOptions};
+use super::backend::{
+    thread_count, Backend, BackendEnvironment, InferenceBackend, LoadOptions, TensorData,
+};
+use super::model::ModelSource;
+use super::options::{SynthesisOptions, SynthesisProgress};
+use super::report::{StageTimings, SynthesisReport};
+use super::signature::ModelSignature;
+use super::variant::ModelVariant;
+use super::voice::{load_voice_style, VoiceStyle};
+use crate::cmudict::{CmuDict, CmuDictG2P};
+use crate::error::{Error, Result};
+use crate::espeak::g2p::DEFAULT_VOICE;
+use crate::espeak::{Encoding, EspeakIpaTokenizer};
+use crate::japanese::{JapanesePhonemizer, ReadingDictionary};
+use crate::phonemes::{
+    arpabet_to_misaki, ipa_to_misaki, xsampa_to_misaki, PhonemeSubstitution, DEFAULT_FUNCTION_WORDS,
+};
+use crate::text::{split_sentences, HomographResolver, Language, LanguageDetector, PartOfSpeech};
+use ndarray::{Array1, Array2};
+#[cfg(feature = "ort")]
+use ort::{ExecutionProvider, GraphOptimizationLevel};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::io::Cursor;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, debug_span, info, info_span};
 
 pub struct TTSConfig {
+    pub backend: Backend,
     pub model_path: String,
+    pub model_variant: Option<ModelVariant>,
+    /// Model file contents; when set, `model_path` is not read
+    pub model_bytes: Option<Cow<'static, [u8]>>,
     pub tokenizer_path: String,
+    /// Contents of `tokenizer.json`; when set, `tokenizer_path` is not read
+    pub tokenizer_json: Option<String>,
+    /// Phoneme vocabulary; when set, the tokenizer JSON is not read
+    pub vocab: Option<HashMap<String, i64>>,
     pub max_length: usize,
     pub sample_rate: u32,
+    #[cfg(feature = "ort")]
     pub graph_level: GraphOptimizationLevel,
+    /// Directory for optimized copies of the model, reused across starts
+    pub optimized_model_cache: Option<String>,
+    /// Threads used within one operator; `None` lets ONNX Runtime pick
+    pub intra_op_threads: Option<usize>,
+    /// Threads used to run independent operators with parallel execution
+    pub inter_op_threads: Option<usize>,
+    pub parallel_execution: bool,
+    pub memory_arena: bool,
+    pub memory_pattern: bool,
+    pub thread_spinning: bool,
+    #[cfg(feature = "ort")]
     pub execution_provider: Vec<ExecutionProvider>,
+    /// Fail to load instead of falling back to CPU when no listed provider works
+    #[cfg(feature = "ort")]
+    pub strict_execution_providers: bool,
+    pub japanese_dictionary_path: Option<String>,
+    pub language_detection: bool,
+    pub language_voice_paths: Vec<(Language, String)>,
+    pub cmudict_path: Option<String>,
+    pub stress_normalization: bool,
+    pub function_words: Vec<String>,
+    pub homograph_disambiguation: bool,
+    pub homograph_overrides: Vec<(String, PartOfSpeech, String)>,
+    pub pronunciation_overrides: Vec<(String, String)>,
+    pub synthesis_report: bool,
 }
 
 impl TTSConfig {
     pub fn new(model_path: &str, tokenizer_path: &str) -> Self {
         TTSConfig {
+            backend: Backend::default(),
             model_path: model_path.to_string(),
+            model_variant: None,
+            model_bytes: None,
             tokenizer_path: tokenizer_path.to_string(),
+            tokenizer_json: None,
+            vocab: None,
             max_length: 512,
             sample_rate: 24000,
+            #[cfg(feature = "ort")]
             graph_level: GraphOptimizationLevel::Level3,
+            optimized_model_cache: None,
+            intra_op_threads: None,
+            inter_op_threads: None,
+            parallel_execution: true,
+            memory_arena: true,
+            memory_pattern: true,
+            thread_spinning: true,
+            #[cfg(feature = "ort")]
             execution_provider: vec![],
+            #[cfg(feature = "ort")]
+            strict_execution_providers: false,
+            japanese_dictionary_path: None,
+            language_detection: false,
+            language_voice_paths: vec![],
+            cmudict_path: None,
+            stress_normalization: false,
+            function_words: DEFAULT_FUNCTION_WORDS
+                .iter()
+                .map(|word| word.to_string())
+                .collect(),
+            homograph_disambiguation: false,
+            homograph_overrides: vec![],
+            pronunciation_overrides: vec![],
+            synthesis_report: false,
         }
     }
 
+    /// Config for a model and tokenizer already in memory, e.g. from
+    /// `include_bytes!`/`include_str!` or a decrypted asset bundle. Static
+    /// bytes are used without copying.
+    pub fn from_memory(model: impl Into<Cow<'static, [u8]>>, tokenizer_json: &str) -> Self {
+        TTSConfig::new("", "")
+            .with_model_bytes(model)
+            .with_tokenizer_json(tokenizer_json)
+    }
+
+    /// Load the model from these bytes instead of `model_path`.
+    pub fn with_model_bytes(mut self, model: impl Into<Cow<'static, [u8]>>) -> Self {
+        self.model_bytes = Some(model.into());
+        self
+    }
+
+    /// Read the vocabulary from this `tokenizer.json` content instead of
+    /// `tokenizer_path`.
+    pub fn with_tokenizer_json(mut self, json: &str) -> Self {
+        self.tokenizer_json = Some(json.to_string());
+        self
+    }
+
+    /// Use this phoneme-to-id vocabulary instead of reading a tokenizer JSON.
+    pub fn with_vocab(mut self, vocab: HashMap<String, i64>) -> Self {
+        self.vocab = Some(vocab);
+        self
+    }
+
     pub fn with_max_tokens_length(mut self, max_length: usize) -> Self {
         self.max_length = max_length;
         self
@@ -38,31 +150,263 @@ impl TTSConfig {
         self
     }
 
+    /// Precision variant to load when `model_path` is a directory holding the
+    /// ONNX community exports (`model.onnx`, `model_fp16.onnx`,
+    /// `model_quantized.onnx`, `model_q4.onnx`, optionally under `onnx/`).
+    /// A model file path is loaded as given.
+    pub fn with_model_variant(mut self, variant: ModelVariant) -> Self {
+        self.model_variant = Some(variant);
+        self
+    }
+
+    /// Library that runs the model: ONNX Runtime by default, or tract with
+    /// the `tract` feature. tract runs on the CPU and ignores the ONNX
+    /// Runtime specific settings (optimization level and cache, threads,
+    /// memory and execution providers).
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    #[cfg(feature = "ort")]
     pub fn with_graph_optimization_level(mut self, level: GraphOptimizationLevel) -> Self {
         self.graph_level = level;
         self
     }
 
+    /// Save the graph optimized at `graph_level` in `dir` on first load and
+    /// load it directly on later starts, skipping the optimization cost. The
+    /// cached file is keyed by a hash of the model's content and by the ONNX
+    /// Runtime build. At most `Level2` is cached, since
+    /// `Level3` output depends on the CPU; with custom execution providers
+    /// only the basic, portable optimizations are cached.
+    pub fn with_optimized_model_cache(mut self, dir: &str) -> Self {
+        self.optimized_model_cache = Some(dir.to_string());
+        self
+    }
+
+    /// Threads ONNX Runtime uses to parallelize work inside one operator.
+    /// Defaults to one per physical core; lower it on shared hosts.
+    pub fn with_intra_op_threads(mut self, threads: usize) -> Self {
+        self.intra_op_threads = Some(threads);
+        self
+    }
+
+    /// Threads used to run independent operators concurrently. Only used
+    /// with parallel execution.
+    pub fn with_inter_op_threads(mut self, threads: usize) -> Self {
+        self.inter_op_threads = Some(threads);
+        self
+    }
+
+    /// Run independent operators concurrently (the default) or one at a
+    /// time. Sequential execution uses fewer threads and less memory.
+    pub fn with_parallel_execution(mut self, enabled: bool) -> Self {
+        self.parallel_execution = enabled;
+        self
+    }
+
+    /// Pool CPU allocations in an arena. Enabled by default; disabling it
+    /// lowers peak memory at some speed cost.
+    pub fn with_memory_arena(mut self, enabled: bool) -> Self {
+        self.memory_arena = enabled;
+        self
+    }
+
+    /// Reuse memory allocation plans between runs. Enabled by default; the
+    /// plan helps little when input lengths vary a lot.
+    pub fn with_memory_pattern(mut self, enabled: bool) -> Self {
+        self.memory_pattern = enabled;
+        self
+    }
+
+    /// Let idle worker threads spin waiting for work. Enabled by default;
+    /// disabling it frees the CPU between runs at a small latency cost.
+    pub fn with_thread_spinning(mut self, enabled: bool) -> Self {
+        self.thread_spinning = enabled;
+        self
+    }
+
+    /// Execution providers to try in order; the first one that this build
+    /// (see the crate's `cuda`, `coreml`, ... features) and the ONNX Runtime
+    /// library support, and that registers without error (e.g. its CUDA
+    /// libraries load), is used. Without a usable one the model runs on CPU,
+    /// unless `with_strict_execution_providers` is set.
+    /// `KokoroTTS::execution_provider` reports the provider in use.
+    #[cfg(feature = "ort")]
     pub fn with_execution_providers(mut self, providers: Vec<ExecutionProvider>) -> Self {
         self.execution_provider = providers;
         self
     }
+
+    /// Return an error from `KokoroTTS::with_config` when none of the
+    /// execution providers can be used, instead of running on CPU.
+    #[cfg(feature = "ort")]
+    pub fn with_strict_execution_providers(mut self, strict: bool) -> Self {
+        self.strict_execution_providers = strict;
+        self
+    }
+
+    /// Kanji reading dictionary used by `generate_speech_japanese`
+    /// (`surface<TAB>reading[<TAB>accent]` per line).
+    pub fn with_japanese_dictionary(mut self, path: &str) -> Self {
+        self.japanese_dictionary_path = Some(path.to_string());
+        self
+    }
+
+    /// Tag each sentence passed to `generate_speech` with a language and
+    /// phonemize it with the matching espeak voice and Misaki mapping.
+    pub fn with_language_detection(mut self, enabled: bool) -> Self {
+        self.language_detection = enabled;
+        self
+    }
+
+    /// Voice used for sentences detected as `language` when language detection
+    /// is enabled, instead of the voice passed to `generate_speech`.
+    pub fn with_language_voice(mut self, language: Language, voice_path: &str) -> Self {
+        self.language_voice_paths
+            .push((language, voice_path.to_string()));
+        self
+    }
+
+    /// Phonemize plain text with a CMUdict pronunciation dictionary and Rust
+    /// letter-to-sound rules instead of espeak-ng (English only).
+    pub fn with_cmudict(mut self, path: &str) -> Self {
+        self.cmudict_path = Some(path.to_string());
+        self
+    }
+
+    /// Move English stress marks to Misaki positions (right before the vowel)
+    /// and drop stress from function words. Off by default, since it changes
+    /// the phonemes (and audio) of existing input.
+    pub fn with_stress_normalization(mut self, enabled: bool) -> Self {
+        self.stress_normalization = enabled;
+        self
+    }
+
+    /// Words left unstressed by stress normalization, replacing the default
+    /// list of articles, prepositions, pronouns and auxiliaries.
+    pub fn with_function_words(mut self, words: &[&str]) -> Self {
+        self.function_words = words.iter().map(|word| word.to_string()).collect();
+        self
+    }
+
+    /// Pick noun/verb/past-tense pronunciations of English homographs ("read",
+    /// "live", "record", ...) from sentence context. Off by default, since it
+    /// changes the phonemes (and audio) of existing input; homographs added
+    /// with `with_homograph` are resolved either way.
+    pub fn with_homograph_disambiguation(mut self, enabled: bool) -> Self {
+        self.homograph_disambiguation = enabled;
+        self
+    }
+
+    /// Add or replace the Misaki pronunciation of a homograph for one part of
+    /// speech, e.g. `("bass", PartOfSpeech::Noun, "bˈAs")`.
+    pub fn with_homograph(mut self, word: &str, pos: PartOfSpeech, phonemes: &str) -> Self {
+        self.homograph_overrides
+            .push((word.to_string(), pos, phonemes.to_string()));
+        self
+    }
+
+    /// Always pronounce an English word with the given Misaki phonemes.
+    pub fn with_pronunciation(mut self, word: &str, phonemes: &str) -> Self {
+        self.pronunciation_overrides
+            .push((word.to_string(), phonemes.to_string()));
+        self
+    }
+
+    /// Attach a `SynthesisReport` (phonemes, tokens, truncation, timings) to
+    /// every `GeneratedAudio`.
+    pub fn with_synthesis_report(mut self, enabled: bool) -> Self {
+        self.synthesis_report = enabled;
+        self
+    }
+}
+
+// Written out because `GraphOptimizationLevel` is not `Clone`. Owned
+// `model_bytes` are copied; borrow static bytes to avoid it.
+impl Clone for TTSConfig {
+    fn clone(&self) -> Self {
+        TTSConfig {
+            backend: self.backend,
+            model_path: self.model_path.clone(),
+            model_variant: self.model_variant,
+            model_bytes: self.model_bytes.clone(),
+            tokenizer_path: self.tokenizer_path.clone(),
+            tokenizer_json: self.tokenizer_json.clone(),
+            vocab: self.vocab.clone(),
+            max_length: self.max_length,
+            sample_rate: self.sample_rate,
+            #[cfg(feature = "ort")]
+            graph_level: copy_level(&self.graph_level),
+            optimized_model_cache: self.optimized_model_cache.clone(),
+            intra_op_threads: self.intra_op_threads,
+            inter_op_threads: self.inter_op_threads,
+            parallel_execution: self.parallel_execution,
+            memory_arena: self.memory_arena,
+            memory_pattern: self.memory_pattern,
+            thread_spinning: self.thread_spinning,
+            #[cfg(feature = "ort")]
+            execution_provider: self.execution_provider.clone(),
+            #[cfg(feature = "ort")]
+            strict_execution_providers: self.strict_execution_providers,
+            japanese_dictionary_path: self.japanese_dictionary_path.clone(),
+            language_detection: self.language_detection,
+            language_voice_paths: self.language_voice_paths.clone(),
+            cmudict_path: self.cmudict_path.clone(),
+            stress_normalization: self.stress_normalization,
+            function_words: self.function_words.clone(),
+            homograph_disambiguation: self.homograph_disambiguation,
+            homograph_overrides: self.homograph_overrides.clone(),
+            pronunciation_overrides: self.pronunciation_overrides.clone(),
+            synthesis_report: self.synthesis_report,
+        }
+    }
 }
 
+#[non_exhaustive]
 pub struct GeneratedAudio {
     pub samples: Vec<f32>,
     pub sample_rate: u32,
     pub duration_seconds: f32,
+    /// Phoneme symbols missing from the model vocab that were replaced with
+    /// the closest vocab symbol or dropped. Positions are character offsets
+    /// into the Misaki phoneme string of the sentence they came from.
+    pub substitutions: Vec<PhonemeSubstitution>,
+    /// One report per model run (one per sentence when the text is
+    /// synthesized sentence by sentence);
+    /// empty unless `TTSConfig::with_synthesis_report` is enabled.
+    pub reports: Vec<SynthesisReport>,
+    /// Synthesis stopped early through `SynthesisOptions::with_cancellation`;
+    /// the samples hold the chunks finished before that.
+    pub cancelled: bool,
 }
 
 impl GeneratedAudio {
-    pub fn save_to_wav<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+    /// Append another clip recorded at the same sample rate.
+    pub(crate) fn append(&mut self, other: GeneratedAudio) {
+        self.samples.extend(other.samples);
+        self.substitutions.extend(other.substitutions);
+        self.reports.extend(other.reports);
+        self.duration_seconds = self.samples.len() as f32 / self.sample_rate as f32;
+    }
+
+    /// Seconds of processing per second of audio; 0 for an empty clip
+    pub(crate) fn real_time_factor(&self, elapsed: Duration) -> f32 {
+        if self.duration_seconds > 0.0 {
+            elapsed.as_secs_f32() / self.duration_seconds
+        } else {
+            0.0
+        }
+    }
+
+    pub fn save_to_wav<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let bytes = self.to_wav_bytes()?;
-        std::fs::write(path, bytes)?;
+        std::fs::write(path.as_ref(), bytes).map_err(|e| Error::io(path.as_ref(), e))?;
         Ok(())
     }
 
-    pub fn to_wav_bytes(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+    pub fn to_wav_bytes(&self) -> Result<Vec<u8>> {
         let spec = hound::WavSpec {
             channels: 1,
             sample_rate: self.sample_rate,
@@ -70,90 +414,285 @@ impl GeneratedAudio {
             sample_format: hound::SampleFormat::Int,
         };
 
+        let wav_error = |e: hound::Error| Error::output("Failed to encode WAV").with_source(e);
+
         let mut cursor = Cursor::new(Vec::new());
         {
-            let mut writer = hound::WavWriter::new(&mut cursor, spec)?;
+            let mut writer = hound::WavWriter::new(&mut cursor, spec).map_err(wav_error)?;
 
             // Add 0.1 seconds of silence at the beginning
             let silence_samples = (self.sample_rate as f32 * 0.1) as usize;
             for _ in 0..silence_samples {
-                writer.write_sample(0i16)?;
+                writer.write_sample(0i16).map_err(wav_error)?;
             }
 
             // Write the actual audio
             for &sample in &self.samples {
                 // Clamp to prevent overflow
-                let clamped = sample.max(-1.0).min(1.0);
+                let clamped = sample.clamp(-1.0, 1.0);
                 let amplitude = (clamped * i16::MAX as f32) as i16;
-                writer.write_sample(amplitude)?;
+                writer.write_sample(amplitude).map_err(wav_error)?;
             }
 
             // Add 0.1 seconds of silence at the end
             for _ in 0..silence_samples {
-                writer.write_sample(0i16)?;
+                writer.write_sample(0i16).map_err(wav_error)?;
             }
 
-            writer.finalize()?;
+            writer.finalize().map_err(wav_error)?;
         }
 
         Ok(cursor.into_inner())
     }
 }
 
+/// Kokoro text-to-speech engine.
+///
+/// `KokoroTTS` is `Send + Sync` and every method takes `&self`, so one
+/// instance can be shared across threads or async tasks behind an `Arc`.
+/// espeak-ng phonemization is serialized by a process-wide lock because the
+/// library is not reentrant; CMUdict and Japanese G2P, tokenization and ONNX
+/// inference run concurrently.
 pub struct KokoroTTS {
-    session: Session,
-    tokenizer: EspeakIpaTokenizer,
+    backend: Box<dyn InferenceBackend>,
+    // The text front end is shared by engines built together for a pool
+    tokenizer: Arc<EspeakIpaTokenizer>,
+    japanese: Arc<JapanesePhonemizer>,
+    language_detector: Option<Arc<LanguageDetector>>,
+    language_voices: Arc<HashMap<Language, VoiceStyle>>,
     sample_rate: u32,
+    synthesis_report: bool,
+    model_variant: ModelVariant,
+    execution_provider: &'static str,
+    /// Input and output layout of the loaded export
+    signature: Arc<ModelSignature>,
 }
 
+// Keep the thread-safety guarantee above from regressing silently
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<KokoroTTS>();
+    assert_send_sync::<VoiceStyle>();
+    assert_send_sync::<GeneratedAudio>();
+    assert_send_sync::<Error>();
+};
+
 impl KokoroTTS {
-    pub fn with_config(config: TTSConfig) -> Result<Self, Box<dyn Error>> {
+    pub fn with_config(config: TTSConfig) -> Result<Self> {
+        let mut engines = Self::with_shared_sessions(config, 1)?;
+        Ok(engines.remove(0))
+    }
+
+    /// Load a model and `tokenizer.json` held in memory with default
+    /// settings. The model bytes are copied; use `TTSConfig::from_memory`
+    /// with static or owned bytes to avoid the copy.
+    pub fn from_memory(model: &[u8], tokenizer_json: &str) -> Result<Self> {
+        Self::with_config(TTSConfig::from_memory(model.to_vec(), tokenizer_json))
+    }
+
+    /// Build `sessions` engines from one config. They share a single ONNX
+    /// Runtime environment and the text front end (tokenizer, dictionaries,
+    /// voices); each gets its own inference session.
+    pub(crate) fn with_shared_sessions(config: TTSConfig, sessions: usize) -> Result<Vec<Self>> {
+        let env = BackendEnvironment::new()?;
+        Self::with_environment(&env, config, sessions)
+    }
+
+    /// Like `with_shared_sessions`, in an existing environment.
+    #[cfg_attr(not(feature = "ort"), allow(unused_variables))]
+    pub(crate) fn with_environment(
+        env: &BackendEnvironment,
+        config: TTSConfig,
+        sessions: usize,
+    ) -> Result<Vec<Self>> {
         let TTSConfig {
+            backend,
             model_path,
+            model_variant,
+            model_bytes,
             tokenizer_path,
+            tokenizer_json,
+            vocab,
             max_length,
             sample_rate,
+            #[cfg(feature = "ort")]
             graph_level,
+            optimized_model_cache,
+            intra_op_threads,
+            inter_op_threads,
+            parallel_execution,
+            memory_arena,
+            memory_pattern,
+            thread_spinning,
+            #[cfg(feature = "ort")]
             execution_provider,
+            #[cfg(feature = "ort")]
+            strict_execution_providers,
+            japanese_dictionary_path,
+            language_detection,
+            language_voice_paths,
+            cmudict_path,
+            stress_normalization,
+            function_words,
+            homograph_disambiguation,
+            homograph_overrides,
+            pronunciation_overrides,
+            synthesis_report,
         } = config;
 
-        let env = Arc::new(Environment::builder().with_name("kokoro_tts").build()?);
+        let intra_op_threads = thread_count(intra_op_threads, "intra-op")?;
+        let inter_op_threads = thread_count(inter_op_threads, "inter-op")?;
 
-        let optimization = match graph_level {
-            GraphOptimizationLevel::Disable => GraphOptimizationLevel::Disable,
-            GraphOptimizationLevel::Level1 => GraphOptimizationLevel::Level1,
-            GraphOptimizationLevel::Level2 => GraphOptimizationLevel::Level2,
-            GraphOptimizationLevel::Level3 => GraphOptimizationLevel::Level3,
+        let (model, model_variant) = match model_bytes {
+            Some(bytes) => (
+                ModelSource::Memory(Arc::new(bytes)),
+                model_variant.unwrap_or_default(),
+            ),
+            None => {
+                let (model_path, model_variant) = resolve_model(&model_path, model_variant);
+                std::fs::metadata(&model_path).map_err(|e| Error::io(&model_path, e))?;
+                (ModelSource::File(model_path), model_variant)
+            }
         };
 
-        let mut builder = SessionBuilder::new(&env)?
-            .with_optimization_level(optimization)?
-            .with_parallel_execution(true)?;
+        let options = LoadOptions {
+            backend,
+            model_variant,
+            #[cfg(feature = "ort")]
+            ort: OrtOptions {
+                graph_level,
+                optimized_model_cache,
+                intra_op_threads,
+                inter_op_threads,
+                parallel_execution,
+                memory_arena,
+                memory_pattern,
+                thread_spinning,
+                execution_providers: execution_provider,
+                strict_execution_providers,
+            },
+        };
+        let session_list = super::backend::load(env, options, &model, sessions)?;
+        let signature = match session_list.first() {
+            Some(backend) => Arc::new(ModelSignature::from_backend(backend.as_ref())?),
+            None => return Ok(vec![]),
+        };
+        info!(%model, %signature, "model signature");
+
+        let vocab = match (vocab, tokenizer_json) {
+            (Some(vocab), _) => vocab,
+            (None, Some(json)) => parse_vocab(&json, "tokenizer JSON")?,
+            (None, None) => {
+                let content = std::fs::read_to_string(&tokenizer_path)
+                    .map_err(|e| Error::io(&tokenizer_path, e))?;
+                parse_vocab(&content, &format!("'{}'", tokenizer_path))?
+            }
+        };
 
-        if !execution_provider.is_empty() {
-            builder = builder.with_execution_providers(&execution_provider)?;
+        let mut tokenizer = match cmudict_path {
+            Some(path) => {
+                EspeakIpaTokenizer::with_cmudict(vocab, CmuDictG2P::new(CmuDict::load(&path)?))?
+            }
+            None => EspeakIpaTokenizer::new(vocab)?,
         }
+        .with_model_max_length(max_length);
 
-        let session = builder.with_model_from_file(&model_path)?;
+        if stress_normalization {
+            let function_words: Vec<&str> = function_words.iter().map(String::as_str).collect();
+            tokenizer = tokenizer.with_stress_normalization(&function_words);
+        }
 
-        let tokenizer_content = std::fs::read_to_string(&tokenizer_path)?;
-        let tokenizer_json: serde_json::Value = serde_json::from_str(&tokenizer_content)?;
-        let vocab_obj = tokenizer_json["model"]["vocab"]
-            .as_object()
-            .ok_or("No vocab found in tokenizer.json")?;
+        if homograph_disambiguation
+            || !homograph_overrides.is_empty()
+            || !pronunciation_overrides.is_empty()
+        {
+            let mut resolver = if homograph_disambiguation {
+                HomographResolver::new()
+            } else {
+                HomographResolver::empty()
+            };
+            for (word, pos, phonemes) in &homograph_overrides {
+                resolver.insert_homograph(word, *pos, phonemes);
+            }
+            for (word, phonemes) in &pronunciation_overrides {
+                resolver.insert_pronunciation(word, phonemes);
+            }
+            tokenizer = tokenizer.with_homographs(resolver);
+        }
 
-        let mut vocab = std::collections::HashMap::new();
-        for (token, id) in vocab_obj {
-            vocab.insert(token.clone(), id.as_i64().unwrap_or(0));
+        let mut japanese = JapanesePhonemizer::new();
+        if let Some(path) = japanese_dictionary_path {
+            japanese = japanese.with_dictionary(ReadingDictionary::load(&path)?);
         }
 
-        let tokenizer = EspeakIpaTokenizer::new(vocab)?.with_model_max_length(max_length);
+        let language_detector = language_detection.then(LanguageDetector::new);
+        let mut language_voices = HashMap::new();
+        for (language, path) in language_voice_paths {
+            language_voices.insert(language, load_voice_style(&path)?);
+        }
 
-        Ok(KokoroTTS {
-            session,
-            tokenizer,
-            sample_rate,
-        })
+        let tokenizer = Arc::new(tokenizer);
+        let japanese = Arc::new(japanese);
+        let language_detector = language_detector.map(Arc::new);
+        let language_voices = Arc::new(language_voices);
+
+        Ok(session_list
+            .into_iter()
+            .map(|backend| KokoroTTS {
+                execution_provider: backend.execution_provider(),
+                backend,
+                tokenizer: Arc::clone(&tokenizer),
+                japanese: Arc::clone(&japanese),
+                language_detector: language_detector.clone(),
+                language_voices: Arc::clone(&language_voices),
+                sample_rate,
+                synthesis_report,
+                model_variant,
+                signature: Arc::clone(&signature),
+            })
+            .collect())
+    }
+
+    /// Misaki phonemes `generate_speech` would synthesize for `text`,
+    /// including homograph, stress and language detection handling.
+    pub fn phonemize(&self, text: &str) -> Result<String> {
+        let Some(detector) = &self.language_detector else {
+            return self.tokenizer.phonemize(text, DEFAULT_VOICE);
+        };
+
+        let mut sentences = Vec::new();
+        for sentence in split_sentences(text) {
+            let language = detector.detect(sentence);
+            sentences.push(
+                self.tokenizer
+                    .phonemize(sentence, language.espeak_voice())?,
+            );
+        }
+        Ok(sentences.join(" "))
+    }
+
+    /// Token ids for `text`, with BOS/EOS and truncated to the configured
+    /// max length, ready to pass to `generate_from_tokens`.
+    pub fn tokenize(&self, text: &str) -> Result<Vec<i64>> {
+        let phonemes = self.phonemize(text)?;
+        Ok(self.tokenizer.encode_phonemes(&phonemes, None)?.ids)
+    }
+
+    /// Phoneme string for token ids, such as those returned by `tokenize`.
+    pub fn decode(&self, ids: &[i64]) -> String {
+        self.tokenizer.decode(ids)
+    }
+
+    /// Precision variant of the loaded model, from the config or the file name.
+    pub fn model_variant(&self) -> ModelVariant {
+        self.model_variant
+    }
+
+    /// ONNX Runtime name of the execution provider the session was created
+    /// with, e.g. `"CUDAExecutionProvider"` or `"CPUExecutionProvider"`, or
+    /// `"tract"` with the tract backend.
+    pub fn execution_provider(&self) -> &'static str {
+        self.execution_provider
     }
 
     pub fn generate_speech_from_phonemes(
@@ -161,10 +700,63 @@ impl KokoroTTS {
         phonemes: &str,
         voice_style: &VoiceStyle,
         speed: f32,
-    ) -> Result<GeneratedAudio, Box<dyn Error>> {
-        let tokens = self.tokenizer.encode_phonemes(phonemes, None)?;
+    ) -> Result<GeneratedAudio> {
+        let encoding = self.tokenizer.encode_phonemes(phonemes, None)?;
+
+        self.generate_from_encoding(encoding, voice_style, speed)
+    }
+
+    /// Synthesize from standard IPA (e.g. pasted from Wiktionary). The IPA is
+    /// normalized to Misaki phonemes first; symbols with no Misaki equivalent
+    /// are reported as a `PhonemeConversionError` with their positions.
+    pub fn generate_speech_from_ipa(
+        &self,
+        ipa: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        let phonemes = ipa_to_misaki(ipa)?;
+
+        self.generate_speech_from_phonemes(&phonemes, voice_style, speed)
+    }
+
+    /// Synthesize from ARPAbet phones as found in CMUdict (`HH AH0 L OW1 | W ER1 L D`).
+    /// Words are separated by `|`; stress digits map to Misaki stress marks.
+    pub fn generate_speech_from_arpabet(
+        &self,
+        arpabet: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        let phonemes = arpabet_to_misaki(arpabet)?;
+
+        self.generate_speech_from_phonemes(&phonemes, voice_style, speed)
+    }
+
+    /// Synthesize from X-SAMPA (`h@"l@U w3:ld`), transliterated to IPA and
+    /// normalized the same way as `generate_speech_from_ipa`.
+    pub fn generate_speech_from_xsampa(
+        &self,
+        xsampa: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        let phonemes = xsampa_to_misaki(xsampa)?;
 
-        self.generate_from_tokens(&tokens, voice_style, speed)
+        self.generate_speech_from_phonemes(&phonemes, voice_style, speed)
+    }
+
+    /// Synthesize Japanese text (hiragana, katakana and kanji covered by the
+    /// reading dictionary) with one of the `jf_*`/`jm_*` voices.
+    pub fn generate_speech_japanese(
+        &self,
+        text: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        let phonemes = self.japanese.phonemize(text)?;
+
+        self.generate_speech_from_phonemes(&phonemes, voice_style, speed)
     }
 
     pub fn generate_speech(
@@ -172,10 +764,118 @@ impl KokoroTTS {
         text: &str,
         voice_style: &VoiceStyle,
         speed: f32,
-    ) -> Result<GeneratedAudio, Box<dyn Error>> {
-        let tokens = self.tokenizer.encode(text, None)?;
+    ) -> Result<GeneratedAudio> {
+        let _span = info_span!("generate_speech", text_len = text.len()).entered();
+        if self.language_detector.is_some() {
+            return self.generate_sentences(text, voice_style, speed, &SynthesisOptions::default());
+        }
+
+        let encoding = self
+            .tokenizer
+            .encode_with_voice(text, DEFAULT_VOICE, None)?;
+
+        self.generate_from_encoding(encoding, voice_style, speed)
+    }
+
+    /// Synthesize long text sentence by sentence with cancellation and
+    /// progress reporting. A cancelled job returns the audio produced so far
+    /// with `cancelled` set.
+    pub fn generate_speech_with_options(
+        &self,
+        text: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+        options: &SynthesisOptions,
+    ) -> Result<GeneratedAudio> {
+        let _span = info_span!("generate_speech", text_len = text.len()).entered();
+        self.generate_sentences(text, voice_style, speed, options)
+    }
+
+    /// Synthesize sentence by sentence. With language detection, each
+    /// sentence switches espeak voice, Misaki mapping and (when configured)
+    /// voice style to its detected language.
+    fn generate_sentences(
+        &self,
+        text: &str,
+        voice_style: &VoiceStyle,
+        speed: f32,
+        options: &SynthesisOptions,
+    ) -> Result<GeneratedAudio> {
+        let start = Instant::now();
+        let sentences = split_sentences(text);
+        let mut audio: Option<GeneratedAudio> = None;
+
+        for (index, sentence) in sentences.iter().enumerate() {
+            if options.is_cancelled() {
+                info!(
+                    chunks_done = index,
+                    chunks_total = sentences.len(),
+                    "synthesis cancelled"
+                );
+                let mut partial = audio.unwrap_or_else(|| self.empty_audio());
+                partial.cancelled = true;
+                return Ok(partial);
+            }
 
-        self.generate_from_tokens(&tokens, voice_style, speed)
+            let (espeak_voice, sentence_voice) = match &self.language_detector {
+                Some(detector) => {
+                    let language = detector.detect(sentence);
+                    debug!(language = ?language, sentence_len = sentence.len(), "detected language");
+                    if std::env::var("DEBUG_PHONEMES").is_ok() {
+                        println!("Detected language {:?} for '{}'", language, sentence);
+                    }
+                    (
+                        language.espeak_voice(),
+                        self.language_voices.get(&language).unwrap_or(voice_style),
+                    )
+                }
+                None => (DEFAULT_VOICE, voice_style),
+            };
+
+            let encoding = self
+                .tokenizer
+                .encode_with_voice(sentence, espeak_voice, None)?;
+            let sentence_audio = self.generate_from_encoding(encoding, sentence_voice, speed)?;
+
+            let produced = match audio.as_mut() {
+                Some(audio) => {
+                    audio.append(sentence_audio);
+                    audio
+                }
+                None => audio.insert(sentence_audio),
+            };
+            options.report_progress(SynthesisProgress {
+                chunks_done: index + 1,
+                chunks_total: sentences.len(),
+                audio_seconds: produced.duration_seconds,
+                elapsed: start.elapsed(),
+            });
+        }
+
+        audio.ok_or_else(|| Error::config("No text to synthesize"))
+    }
+
+    fn empty_audio(&self) -> GeneratedAudio {
+        GeneratedAudio {
+            samples: vec![],
+            sample_rate: self.sample_rate,
+            duration_seconds: 0.0,
+            substitutions: vec![],
+            reports: vec![],
+            cancelled: false,
+        }
+    }
+
+    /// Run the model on an encoding and fill in the stages before inference
+    fn generate_from_encoding(
+        &self,
+        encoding: Encoding,
+        voice_style: &VoiceStyle,
+        speed: f32,
+    ) -> Result<GeneratedAudio> {
+        let mut audio = self.generate_from_tokens(&encoding.ids, voice_style, speed)?;
+        attach_encoding(&mut audio, encoding);
+        Ok(audio)
     }
 
     pub fn generate_from_tokens(
@@ -183,48 +883,389 @@ impl KokoroTTS {
         tokens: &[i64],
         voice_style: &VoiceStyle,
         speed: f32,
-    ) -> Result<GeneratedAudio, Box<dyn Error>> {
-        let input_ids = Array2::<i64>::from_shape_vec((1, tokens.len()), tokens.to_vec())?;
+    ) -> Result<GeneratedAudio> {
+        let _span = debug_span!("inference", token_count = tokens.len()).entered();
+        let input_ids = Array2::<i64>::from_shape_vec((1, tokens.len()), tokens.to_vec())
+            .map_err(|e| Error::inference("Invalid token ids").with_source(e))?;
         // Use token length to select the appropriate style vector, matching Python implementation
-        let style_vector = voice_style.get_style_vector_for_token_length(tokens.len(), 256);
-        let style = Array2::<f32>::from_shape_vec((1, 256), style_vector)?;
+        let style_vector = self.style_vector(voice_style, tokens.len())?;
+        let style = Array2::<f32>::from_shape_vec((1, style_vector.len()), style_vector)
+            .map_err(|e| Error::voice_format("Invalid style vector").with_source(e))?;
         let speed_array = Array1::<f32>::from_vec(vec![speed]);
 
-        let input_ids_cow: CowArray<i64, IxDyn> = CowArray::from(input_ids.into_dyn());
-        let style_cow: CowArray<f32, IxDyn> = CowArray::from(style.into_dyn());
-        let speed_cow: CowArray<f32, IxDyn> = CowArray::from(speed_array.into_dyn());
+        let (outputs, inference_time) = self.run_model(input_ids, style, speed_array)?;
 
-        let input_ids_tensor = Value::from_array(self.session.allocator(), &input_ids_cow)?;
-        let style_tensor = Value::from_array(self.session.allocator(), &style_cow)?;
-        let speed_tensor = Value::from_array(self.session.allocator(), &speed_cow)?;
+        let samples = self.signature.waveform(&outputs)?.into_raw_vec();
+        let audio = self.build_audio(tokens, voice_style, samples, inference_time);
+        debug!(
+            token_count = tokens.len(),
+            elapsed_ms = inference_time.as_secs_f64() * 1000.0,
+            audio_seconds = audio.duration_seconds,
+            rtf = audio.real_time_factor(inference_time),
+            "inference finished"
+        );
 
-        let outputs = self
-            .session
-            .run(vec![input_ids_tensor, style_tensor, speed_tensor])?;
+        Ok(audio)
+    }
 
-        if let Ok(output) = outputs[0].try_extract::<f32>() {
-            let view = output.view();
-            let samples = view.as_slice().unwrap().to_vec();
-            let duration_seconds = samples.len() as f32 / self.sample_rate as f32;
+    /// Wrap the samples of one utterance, with a partial report when enabled
+    fn build_audio(
+        &self,
+        tokens: &[i64],
+        voice_style: &VoiceStyle,
+        samples: Vec<f32>,
+        inference_time: Duration,
+    ) -> GeneratedAudio {
+        let mut reports = vec![];
+        if self.synthesis_report {
+            reports.push(SynthesisReport {
+                phonemes: self.tokenizer.decode(tokens),
+                token_ids: tokens.to_vec(),
+                style_index: voice_style
+                    .style_index_for_token_length(tokens.len(), self.signature.style_width),
+                timings: StageTimings {
+                    inference: inference_time,
+                    ..StageTimings::default()
+                },
+                ..SynthesisReport::default()
+            });
+        }
 
-            let audio = GeneratedAudio {
-                samples,
-                sample_rate: self.sample_rate,
-                duration_seconds,
+        GeneratedAudio {
+            duration_seconds: samples.len() as f32 / self.sample_rate as f32,
+            samples,
+            sample_rate: self.sample_rate,
+            substitutions: vec![],
+            reports,
+            cancelled: false,
+        }
+    }
+
+    /// Style vector for an utterance of `token_length` tokens, sized for the model
+    fn style_vector(&self, voice_style: &VoiceStyle, token_length: usize) -> Result<Vec<f32>> {
+        let width = self.signature.style_width;
+        if voice_style.vector_size != width {
+            return Err(Error::voice_format(format!(
+                "Voice has {}-value style vectors but the model expects {}",
+                voice_style.vector_size, width
+            )));
+        }
+        Ok(voice_style.get_style_vector_for_token_length(token_length, width))
+    }
+
+    /// Feed ids, styles and speeds to the model as its signature requires
+    fn run_model(
+        &self,
+        input_ids: Array2<i64>,
+        style: Array2<f32>,
+        speed: Array1<f32>,
+    ) -> Result<(Vec<TensorData>, Duration)> {
+        let tensors = self.signature.bind(input_ids, style, speed)?;
+
+        let inference_start = Instant::now();
+        let outputs = self.backend.run(tensors)?;
+        Ok((outputs, inference_start.elapsed()))
+    }
+
+    /// Synthesize many short utterances, returning their audio in input order.
+    ///
+    /// When the model accepts a dynamic batch dimension and reports per-token
+    /// durations, utterances of similar token length run together: `input_ids`
+    /// are padded and each item gets its own style vector and speed, then each
+    /// waveform is cut back to the length of its real tokens. Other models, and
+    /// configs with language detection, run the utterances one at a time.
+    ///
+    /// Batched audio is close to, but not the same as, what `generate_speech`
+    /// produces for the same item: the model has no attention mask, so the
+    /// padding changes the context of the real tokens. Items longer than the
+    /// model's `max_length` are synthesized sentence by sentence on either
+    /// path rather than truncated.
+    pub fn generate_batch(
+        &self,
+        items: &[(&str, &VoiceStyle, f32)],
+    ) -> Result<Vec<GeneratedAudio>> {
+        let _span = info_span!("generate_batch", items = items.len()).entered();
+        let batch_output = self
+            .signature
+            .duration_output
+            .filter(|_| self.signature.dynamic_batch);
+        let duration_output = match batch_output {
+            Some(index) if self.language_detector.is_none() => index,
+            _ => {
+                debug!("model does not support batching, running sequentially");
+                return items
+                    .iter()
+                    .map(|&(text, voice_style, speed)| {
+                        if self.language_detector.is_some() {
+                            return self.generate_speech(text, voice_style, speed);
+                        }
+                        match self.encode_whole(text)? {
+                            Some(encoding) => {
+                                self.generate_from_encoding(encoding, voice_style, speed)
+                            }
+                            None => self.generate_sentences(
+                                text,
+                                voice_style,
+                                speed,
+                                &SynthesisOptions::default(),
+                            ),
+                        }
+                    })
+                    .collect();
+            }
+        };
+
+        let encodings = items
+            .iter()
+            .map(|(text, _, _)| self.encode_whole(text))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut results: Vec<Option<GeneratedAudio>> = items.iter().map(|_| None).collect();
+        let mut whole = Vec::with_capacity(items.len());
+        for (i, encoding) in encodings.iter().enumerate() {
+            match encoding {
+                Some(_) => whole.push(i),
+                None => {
+                    let (text, voice_style, speed) = items[i];
+                    results[i] = Some(self.generate_sentences(
+                        text,
+                        voice_style,
+                        speed,
+                        &SynthesisOptions::default(),
+                    )?);
+                }
+            }
+        }
+
+        let ids = |i: usize| encodings[i].as_ref().map_or(&[][..], |e| e.ids.as_slice());
+        let lengths: Vec<usize> = whole.iter().map(|&i| ids(i).len()).collect();
+        for group in group_by_length(&lengths) {
+            let group: Vec<usize> = group.into_iter().map(|g| whole[g]).collect();
+            let batch: Vec<(&[i64], &VoiceStyle, f32)> = group
+                .iter()
+                .map(|&i| (ids(i), items[i].1, items[i].2))
+                .collect();
+            let audios = match batch.as_slice() {
+                // Nothing to pad; some exports also drop the batch axis here
+                [(ids, voice_style, speed)] => {
+                    vec![self.generate_from_tokens(ids, voice_style, *speed)?]
+                }
+                _ => self.run_batch(&batch, duration_output)?,
             };
+            for (&i, audio) in group.iter().zip(audios) {
+                results[i] = Some(audio);
+            }
+        }
 
-            Ok(audio)
-        } else {
-            Err("Failed to extract audio output".into())
+        encodings
+            .into_iter()
+            .zip(results)
+            .map(|(encoding, audio)| {
+                let mut audio =
+                    audio.ok_or_else(|| Error::output("Batched run returned too few outputs"))?;
+                if let Some(encoding) = encoding {
+                    attach_encoding(&mut audio, encoding);
+                }
+                Ok(audio)
+            })
+            .collect()
+    }
+
+    /// Encode a batch item in full, or `None` when it doesn't fit the model's
+    /// input and has to be synthesized sentence by sentence
+    fn encode_whole(&self, text: &str) -> Result<Option<Encoding>> {
+        let encoding = self
+            .tokenizer
+            .encode_with_voice(text, DEFAULT_VOICE, Some(usize::MAX))?;
+        if encoding.ids.len() > self.tokenizer.model_max_length() {
+            debug!(
+                token_count = encoding.ids.len(),
+                "batch item too long, synthesizing it sentence by sentence"
+            );
+            return Ok(None);
         }
+        Ok(Some(encoding))
     }
 
-    #[allow(dead_code)]
-    pub fn speak(
+    /// One model run over padded utterances
+    fn run_batch(
         &self,
-        text: &str,
-        voice_style: &VoiceStyle,
-    ) -> Result<GeneratedAudio, Box<dyn Error>> {
+        batch: &[(&[i64], &VoiceStyle, f32)],
+        duration_output: usize,
+    ) -> Result<Vec<GeneratedAudio>> {
+        let max_len = batch.iter().map(|(ids, _, _)| ids.len()).max().unwrap_or(0);
+        let _span = debug_span!("inference_batch", batch_size = batch.len(), max_len).entered();
+
+        let mut input_ids = Vec::with_capacity(batch.len() * max_len);
+        let width = self.signature.style_width;
+        let mut styles = Vec::with_capacity(batch.len() * width);
+        let mut speeds = Vec::with_capacity(batch.len());
+        for (ids, voice_style, speed) in batch {
+            input_ids.extend_from_slice(ids);
+            input_ids.resize(
+                input_ids.len() + max_len - ids.len(),
+                self.tokenizer.pad_id(),
+            );
+            // Style follows each item's real length, as in a single run
+            styles.extend(self.style_vector(voice_style, ids.len())?);
+            speeds.push(*speed);
+        }
+
+        let input_ids = Array2::<i64>::from_shape_vec((batch.len(), max_len), input_ids)
+            .map_err(|e| Error::inference("Invalid token ids").with_source(e))?;
+        let style = Array2::<f32>::from_shape_vec((batch.len(), width), styles)
+            .map_err(|e| Error::voice_format("Invalid style vector").with_source(e))?;
+        let speed_array = Array1::<f32>::from_vec(speeds);
+
+        let (outputs, inference_time) = self.run_model(input_ids, style, speed_array)?;
+
+        let waveform = self.signature.waveform(&outputs)?;
+        let durations = extract_durations(&outputs[duration_output], batch.len())?;
+        if waveform.ndim() != 2 || waveform.shape()[0] != batch.len() {
+            return Err(Error::output(format!(
+                "Expected a [{}, samples] waveform from a batched run, got {:?}",
+                batch.len(),
+                waveform.shape()
+            )));
+        }
+
+        // Every item is padded to the longest predicted frame count, which
+        // gives the number of samples per duration frame
+        let max_frames = durations
+            .iter()
+            .map(|row| row.iter().sum::<f64>())
+            .fold(0.0, f64::max);
+        let total_samples = waveform.shape()[1];
+        let samples_per_frame = if max_frames > 0.0 {
+            total_samples as f64 / max_frames
+        } else {
+            0.0
+        };
+
+        let audios = batch
+            .iter()
+            .zip(&durations)
+            .zip(waveform.outer_iter())
+            .map(|(((ids, voice_style, _), row_durations), row)| {
+                let frames: f64 = row_durations.iter().take(ids.len()).sum();
+                let length = ((frames * samples_per_frame).round() as usize).min(total_samples);
+                let samples = row.iter().take(length).copied().collect();
+                self.build_audio(ids, voice_style, samples, inference_time)
+            })
+            .collect();
+
+        debug!(
+            batch_size = batch.len(),
+            max_len,
+            elapsed_ms = inference_time.as_secs_f64() * 1000.0,
+            "batched inference finished"
+        );
+        Ok(audios)
+    }
+
+    #[allow(dead_code)]
+    pub fn speak(&self, text: &str, voice_style: &VoiceStyle) -> Result<GeneratedAudio> {
         self.generate_speech(text, voice_style, 1.0)
     }
 }
+
+/// Phoneme vocabulary from the `model.vocab` object of a tokenizer JSON
+fn parse_vocab(json: &str, origin: &str) -> Result<HashMap<String, i64>> {
+    let tokenizer_json: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| Error::tokenizer(format!("Invalid JSON in {}", origin)).with_source(e))?;
+    let vocab_obj = tokenizer_json["model"]["vocab"]
+        .as_object()
+        .ok_or_else(|| Error::tokenizer("No vocab found in tokenizer.json"))?;
+
+    let mut vocab = HashMap::new();
+    for (token, id) in vocab_obj {
+        vocab.insert(token.clone(), id.as_i64().unwrap_or(0));
+    }
+    Ok(vocab)
+}
+
+/// The model file to load and its precision variant
+fn resolve_model(model_path: &str, variant: Option<ModelVariant>) -> (String, ModelVariant) {
+    let path = Path::new(model_path);
+    if path.is_dir() {
+        let variant = variant.unwrap_or_default();
+        (variant.locate(path).to_string_lossy().into_owned(), variant)
+    } else {
+        let variant = variant.unwrap_or_else(|| ModelVariant::from_file_name(path));
+        (model_path.to_string(), variant)
+    }
+}
+
+/// Largest number of utterances per batched model run
+const MAX_BATCH_SIZE: usize = 16;
+/// Longest run of pad tokens allowed in a batch; padding changes the model's
+/// context for the real tokens, so only similar lengths are grouped
+const MAX_BATCH_PADDING: usize = 16;
+
+/// Indices of utterances grouped into batches of similar token length
+fn group_by_length(lengths: &[usize]) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..lengths.len()).collect();
+    order.sort_by_key(|&i| lengths[i]);
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for i in order {
+        match groups.last_mut() {
+            Some(group)
+                if group.len() < MAX_BATCH_SIZE
+                    && lengths[i] - lengths[group[0]] <= MAX_BATCH_PADDING =>
+            {
+                group.push(i)
+            }
+            _ => groups.push(vec![i]),
+        }
+    }
+    groups
+}
+
+/// Per-token durations as `[batch][token]`, from an i64 or float output
+fn extract_durations(output: &TensorData, batch_size: usize) -> Result<Vec<Vec<f64>>> {
+    let rows = |values: Vec<f64>| {
+        let width = values.len() / batch_size.max(1);
+        values
+            .chunks(width.max(1))
+            .map(<[f64]>::to_vec)
+            .collect::<Vec<_>>()
+    };
+
+    let durations = match output {
+        TensorData::Int64(tensor) => rows(tensor.iter().map(|&d| d as f64).collect()),
+        TensorData::Int32(tensor) => rows(tensor.iter().map(|&d| f64::from(d)).collect()),
+        _ => {
+            let tensor = output.to_f32().ok_or_else(|| {
+                Error::output(format!(
+                    "Durations output has type {:?}",
+                    output.element_type()
+                ))
+            })?;
+            rows(tensor.iter().map(|&d| d as f64).collect())
+        }
+    };
+
+    if durations.len() != batch_size {
+        return Err(Error::output(format!(
+            "Expected durations for {} utterances, got {}",
+            batch_size,
+            durations.len()
+        )));
+    }
+    Ok(durations)
+}
+
+/// Copy the front-end stages of an encoding onto audio synthesized from it
+fn attach_encoding(audio: &mut GeneratedAudio, encoding: Encoding) {
+    if let Some(report) = audio.reports.first_mut() {
+        report.espeak_ipa = encoding.espeak_ipa;
+        report.phonemes = encoding.phonemes;
+        report.truncated = encoding.dropped_tokens > 0;
+        report.dropped_tokens = encoding.dropped_tokens;
+        report.unknown_symbols = encoding.substitutions.clone();
+        report.timings.g2p = encoding.g2p_time;
+        report.timings.tokenization = encoding.tokenize_time;
+    }
+    audio.substitutions = encoding.substitutions;
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Precision variants of the Kokoro ONNX export, as published by the ONNX
+/// community (`onnx/model*.onnx`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ModelVariant {
+    /// Full precision (`model.onnx`)
+    #[default]
+    Full,
+    /// Half precision weights and activations (`model_fp16.onnx`)
+    Fp16,
+    /// Dynamically quantized int8 weights (`model_quantized.onnx`)
+    Quantized,
+    /// 4-bit weights (`model_q4.onnx`)
+    Q4,
+}
+
+impl ModelVariant {
+    pub const ALL: [ModelVariant; 4] = [
+        ModelVariant::Full,
+        ModelVariant::Fp16,
+        ModelVariant::Quantized,
+        ModelVariant::Q4,
+    ];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ModelVariant::Full => "model.onnx",
+            ModelVariant::Fp16 => "model_fp16.onnx",
+            ModelVariant::Quantized => "model_quantized.onnx",
+            ModelVariant::Q4 => "model_q4.onnx",
+        }
+    }
+
+    /// Guess the variant of a model file from its name, e.g. `kokoro_fp16.onnx`.
+    pub fn from_file_name(path: &Path) -> Self {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        // Quantized suffixes first: `q4f16` and `q8f16` also end in `f16`
+        const QUANTIZED: &[&str] = &["quantized", "int8", "uint8", "q8", "q8f16"];
+        if name.ends_with("q4") || name.ends_with("q4f16") {
+            ModelVariant::Q4
+        } else if QUANTIZED.iter().any(|suffix| name.ends_with(suffix)) {
+            ModelVariant::Quantized
+        } else if name.ends_with("fp16") || name.ends_with("f16") {
+            ModelVariant::Fp16
+        } else {
+            ModelVariant::Full
+        }
+    }
+
+    /// The file to load for this variant when the model path is a directory:
+    /// `<dir>/onnx/<file>` (the Hugging Face layout) if present, else
+    /// `<dir>/<file>`.
+    pub(crate) fn locate(&self, dir: &Path) -> PathBuf {
+        let nested = dir.join("onnx").join(self.file_name());
+        if nested.is_file() {
+            nested
+        } else {
+            dir.join(self.file_name())
+        }
+    }
+}
+
+impl fmt::Display for ModelVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ModelVariant::Full => "fp32",
+            ModelVariant::Fp16 => "fp16",
+            ModelVariant::Quantized => "int8",
+            ModelVariant::Q4 => "q4",
+        };
+        f.write_str(name)
+    }
+}
@@ -1,4 +1,4 @@
-use std::error::Error;
+use crate::error::{Error, Result};
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
@@ -30,29 +30,53 @@ impl VoiceStyle {
         token_length: usize,
         vector_size: usize,
     ) -> Vec<f32> {
+        let offset =
+            self.style_index_for_token_length(token_length, vector_size) * self.vector_size;
+
+        if offset + vector_size <= self.data.len() {
+            self.data[offset..offset + vector_size].to_vec()
+        } else {
+            // Fallback to the first vector
+            self.get_style_vector(vector_size)
+        }
+    }
+
+    /// Row of the style matrix used for `token_length` tokens
+    pub fn style_index_for_token_length(&self, token_length: usize, vector_size: usize) -> usize {
         // Select style vector based on token length, matching Python implementation
         // voices[len(tokens)] where voices has shape (-1, 1, 256)
         let offset = token_length * self.vector_size;
 
         if offset + vector_size <= self.data.len() {
-            self.data[offset..offset + vector_size].to_vec()
+            token_length
         } else {
             // If the requested offset is out of bounds, use the last available vector
             let last_vector_start = (self.data.len() / self.vector_size) * self.vector_size;
             if last_vector_start + vector_size <= self.data.len() {
-                self.data[last_vector_start..last_vector_start + vector_size].to_vec()
+                last_vector_start / self.vector_size
             } else {
-                // Fallback to the first vector
-                self.get_style_vector(vector_size)
+                0
             }
         }
     }
 }
 
-pub fn load_voice_style<P: AsRef<Path>>(path: P) -> Result<VoiceStyle, Box<dyn Error>> {
-    let mut file = File::open(path)?;
+pub fn load_voice_style<P: AsRef<Path>>(path: P) -> Result<VoiceStyle> {
+    let path = path.as_ref();
     let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)?;
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut buffer))
+        .map_err(|e| Error::io(path, e))?;
+
+    // Each style vector is 256 little-endian f32 values
+    if buffer.is_empty() || buffer.len() % (256 * 4) != 0 {
+        return Err(Error::voice_format(format!(
+            "'{}' is {} bytes, expected a non-empty multiple of {} (256 f32 values per style vector)",
+            path.display(),
+            buffer.len(),
+            256 * 4
+        )));
+    }
 
     // Convert bytes to f32 array (assuming little-endian)
     let style_data: Vec<f32> = buffer
@@ -8,7 +8,7 @@
 //! ```no_run
 //! use kokoroxide::{KokoroTTS, TTSConfig, load_voice_style};
 //!
-//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # fn main() -> kokoroxide::Result<()> {
 //! // Configure and initialize Kokoro TTS
 //! let config = TTSConfig::new("path/to/model.onnx", "path/to/tokenizer.json");
 //! let tts = KokoroTTS::with_config(config)?;
@@ -26,20 +26,39 @@
 //! ```
 
 // Internal modules - not exposed to library users
+mod cmudict;
+mod error;
 mod espeak;
+#[cfg(feature = "playback")]
 #[allow(dead_code)]
 mod interactive;
+mod japanese;
+mod phonemes;
+#[cfg(feature = "playback")]
 #[allow(dead_code)]
 mod playback;
+// The test runners configure ONNX Runtime directly and play audio
+#[cfg(all(feature = "ort", feature = "playback"))]
 #[allow(dead_code)]
 mod test;
+mod text;
 
 // Public API modules
 /// Kokoro TTS synthesis engine and related types
 pub mod kokoro;
 
 // Re-export main types for convenience
-pub use kokoro::{load_voice_style, GeneratedAudio, KokoroTTS, TTSConfig, VoiceStyle};
+pub use error::{Error, Result};
+pub use kokoro::{
+    load_voice_style, Backend, CancellationToken, GeneratedAudio, KokoroPool, KokoroRuntime,
+    KokoroTTS, Language, ModelVariant, PartOfSpeech, PhonemeConversionError, PhonemeSubstitution,
+    PoolStats, StageTimings, SynthesisOptions, SynthesisProgress, SynthesisReport, TTSConfig,
+    VoiceStyle,
+};
+
+#[cfg(feature = "async")]
+pub use kokoro::{AsyncKokoroTTS, AudioStream};
 
 // Re-export ONNX GraphOptimizationLevel for configuration
+#[cfg(feature = "ort")]
 pub use ort::GraphOptimizationLevel;
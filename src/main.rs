@@ -1,11 +1,16 @@
 use clap::{Parser, Subcommand};
 
 // Internal modules for the binary
+mod cmudict;
+mod error;
 mod espeak;
 mod interactive;
+mod japanese;
 mod kokoro;
+mod phonemes;
 mod playback;
 mod test;
+mod text;
 
 #[derive(Parser)]
 #[command(name = "ronnex")]
@@ -49,6 +54,52 @@ enum TestName {
     DirectPhonemes,
     /// Test raw token input
     RawTokens,
+    /// Test the Japanese kana phonemizer
+    Japanese,
+    /// Test per-sentence language detection
+    Multilingual,
+    /// Test standard IPA input
+    IpaInput,
+    /// Test ARPAbet and X-SAMPA input
+    NotationInput,
+    /// Test the CMUdict G2P backend
+    CmuDict,
+    /// Test Misaki stress normalization
+    Stress,
+    /// Test POS-aware homograph disambiguation
+    Homographs,
+    /// Test fallback mapping of out-of-vocabulary phonemes
+    PhonemeFallback,
+    /// Test the public phonemize, tokenize and decode API
+    Phonemize,
+    /// Test synthesis reports and their JSON output
+    SynthesisReport,
+    /// Test typed errors for missing files, bad voices and unconvertible IPA
+    Errors,
+    /// Stress test sharing one KokoroTTS across threads
+    Concurrency,
+    /// Test the session pool, its backpressure and stats
+    Pool,
+    /// Test the async facade and audio streaming
+    Async,
+    /// Test cancellation and progress callbacks
+    SynthesisOptions,
+    /// Compare batched and sequential inference
+    Batch,
+    /// Load the fp32, fp16 and quantized model variants
+    ModelVariants,
+    /// Load the model and tokenizer from memory
+    FromMemory,
+    /// Save and reuse the optimized model graph
+    ModelCache,
+    /// Test thread, execution mode and memory options
+    SessionOptions,
+    /// Test execution provider fallback and strict loading
+    Providers,
+    /// Test loading, unloading and reloading models in one runtime
+    Runtime,
+    /// Compare ONNX Runtime and tract (with the `tract` feature) output
+    Backends,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -77,7 +128,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 interactive::run_interactive()?;
             }
             TestName::Espeak => {
+                #[cfg(feature = "espeak")]
                 test::test_espeak::test_espeak()?;
+                #[cfg(not(feature = "espeak"))]
+                return Err("built without the `espeak` feature".into());
             }
             TestName::EspeakTokenizer => {
                 test::test_espeak_tokenizer::test_espeak_tokenizer()?;
@@ -88,6 +142,78 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             TestName::RawTokens => {
                 test::test_raw_tokens::test_raw_tokens()?;
             }
+            TestName::Japanese => {
+                test::test_japanese::test_japanese(no_play)?;
+            }
+            TestName::Multilingual => {
+                test::test_multilingual::test_multilingual(no_play)?;
+            }
+            TestName::IpaInput => {
+                test::test_ipa_input::test_ipa_input()?;
+            }
+            TestName::NotationInput => {
+                test::test_notation_input::test_notation_input()?;
+            }
+            TestName::CmuDict => {
+                test::test_cmudict::test_cmudict(no_play)?;
+            }
+            TestName::Stress => {
+                test::test_stress::test_stress()?;
+            }
+            TestName::Homographs => {
+                test::test_homographs::test_homographs()?;
+            }
+            TestName::PhonemeFallback => {
+                test::test_phoneme_fallback::test_phoneme_fallback()?;
+            }
+            TestName::Phonemize => {
+                test::test_phonemize::test_phonemize()?;
+            }
+            TestName::SynthesisReport => {
+                test::test_synthesis_report::test_synthesis_report()?;
+            }
+            TestName::Errors => {
+                test::test_errors::test_errors()?;
+            }
+            TestName::Concurrency => {
+                test::test_concurrency::test_concurrency()?;
+            }
+            TestName::Pool => {
+                test::test_pool::test_pool()?;
+            }
+            TestName::Async => {
+                #[cfg(feature = "async")]
+                test::test_async::test_async()?;
+                #[cfg(not(feature = "async"))]
+                return Err("built without the `async` feature".into());
+            }
+            TestName::SynthesisOptions => {
+                test::test_synthesis_options::test_synthesis_options()?;
+            }
+            TestName::Batch => {
+                test::test_batch::test_batch()?;
+            }
+            TestName::ModelVariants => {
+                test::test_model_variants::test_model_variants()?;
+            }
+            TestName::FromMemory => {
+                test::test_from_memory::test_from_memory()?;
+            }
+            TestName::ModelCache => {
+                test::test_model_cache::test_model_cache()?;
+            }
+            TestName::SessionOptions => {
+                test::test_session_options::test_session_options()?;
+            }
+            TestName::Providers => {
+                test::test_providers::test_providers()?;
+            }
+            TestName::Runtime => {
+                test::test_runtime::test_runtime()?;
+            }
+            TestName::Backends => {
+                test::test_backends::test_backends()?;
+            }
         },
         None => {
             println!("No command specified. Use --help for usage information.");
@@ -0,0 +1,150 @@
+use super::PhonemeConversionError;
+
+/// ARPAbet (CMUdict) phones mapped to Misaki. Vowels list their unstressed and
+/// stressed forms separately where Misaki distinguishes them (AH, ER).
+const ARPABET_TO_MISAKI: &[(&str, &str, &str)] = &[
+    // phone, unstressed (0), stressed (1/2)
+    ("AA", "ɑ", "ɑ"),
+    ("AE", "æ", "æ"),
+    ("AH", "ə", "ʌ"),
+    ("AO", "ɔ", "ɔ"),
+    ("AW", "W", "W"),
+    ("AX", "ə", "ə"),
+    ("AXR", "əɹ", "əɹ"),
+    ("AY", "I", "I"),
+    ("EH", "ɛ", "ɛ"),
+    ("ER", "əɹ", "ɜɹ"),
+    ("EY", "A", "A"),
+    ("IH", "ɪ", "ɪ"),
+    ("IX", "ᵻ", "ᵻ"),
+    ("IY", "i", "i"),
+    ("OW", "O", "O"),
+    ("OY", "Y", "Y"),
+    ("UH", "ʊ", "ʊ"),
+    ("UW", "u", "u"),
+    ("UX", "u", "u"),
+    ("B", "b", "b"),
+    ("CH", "ʧ", "ʧ"),
+    ("D", "d", "d"),
+    ("DH", "ð", "ð"),
+    ("DX", "ɾ", "ɾ"),
+    ("EL", "ᵊl", "ᵊl"),
+    ("EM", "ᵊm", "ᵊm"),
+    ("EN", "ᵊn", "ᵊn"),
+    ("F", "f", "f"),
+    ("G", "ɡ", "ɡ"),
+    ("HH", "h", "h"),
+    ("JH", "ʤ", "ʤ"),
+    ("K", "k", "k"),
+    ("L", "l", "l"),
+    ("M", "m", "m"),
+    ("N", "n", "n"),
+    ("NG", "ŋ", "ŋ"),
+    ("NX", "ŋ", "ŋ"),
+    ("P", "p", "p"),
+    ("Q", "t", "t"),
+    ("R", "ɹ", "ɹ"),
+    ("S", "s", "s"),
+    ("SH", "ʃ", "ʃ"),
+    ("T", "t", "t"),
+    ("TH", "θ", "θ"),
+    ("V", "v", "v"),
+    ("W", "w", "w"),
+    ("WH", "w", "w"),
+    ("Y", "j", "j"),
+    ("Z", "z", "z"),
+    ("ZH", "ʒ", "ʒ"),
+];
+
+/// Punctuation tokens passed through (they also separate words)
+const PUNCTUATION: &[&str] = &[",", ".", "!", "?", ";", ":", "—", "…"];
+
+/// Word separator between ARPAbet words, e.g. `HH AH0 L OW1 | W ER1 L D`
+const WORD_SEPARATOR: &str = "|";
+
+/// Convert ARPAbet phones (CMUdict style, `HH AH0 L OW1`) to Misaki phonemes.
+///
+/// Phones are whitespace separated and words are separated by `|`. Stress
+/// digits become `ˈ` (1) and `ˌ` (2) placed right before the vowel, as Misaki
+/// does; `0` or no digit leaves the vowel unstressed.
+pub fn arpabet_to_misaki(arpabet: &str) -> Result<String, PhonemeConversionError> {
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut unknown = Vec::new();
+
+    for (position, token) in tokens_with_positions(arpabet) {
+        if token == WORD_SEPARATOR {
+            push_word(&mut words, &mut current);
+            continue;
+        }
+        if PUNCTUATION.contains(&token) {
+            current.push_str(token);
+            push_word(&mut words, &mut current);
+            continue;
+        }
+
+        match phone_to_misaki(token) {
+            Some(phonemes) => current.push_str(&phonemes),
+            None => unknown.push((position, token.to_string())),
+        }
+    }
+    push_word(&mut words, &mut current);
+
+    if !unknown.is_empty() {
+        return Err(PhonemeConversionError {
+            notation: "ARPAbet",
+            input: arpabet.to_string(),
+            symbols: unknown,
+        });
+    }
+
+    Ok(words.join(" "))
+}
+
+/// Convert a single ARPAbet phone with an optional stress digit.
+pub fn phone_to_misaki(phone: &str) -> Option<String> {
+    let upper = phone.to_ascii_uppercase();
+    let (base, stress) = match upper.chars().last() {
+        Some(digit @ '0'..='2') => (&upper[..upper.len() - 1], Some(digit)),
+        _ => (upper.as_str(), None),
+    };
+
+    let &(_, unstressed, stressed) = ARPABET_TO_MISAKI.iter().find(|(p, _, _)| *p == base)?;
+
+    Some(match stress {
+        Some('1') => format!("ˈ{}", stressed),
+        Some('2') => format!("ˌ{}", stressed),
+        _ => unstressed.to_string(),
+    })
+}
+
+fn push_word(words: &mut Vec<String>, current: &mut String) {
+    if !current.is_empty() {
+        words.push(std::mem::take(current));
+    }
+}
+
+/// Whitespace-separated tokens with their character offsets. `|` is split off
+/// even when written without surrounding spaces.
+fn tokens_with_positions(input: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+
+    for (char_index, (byte_index, c)) in input.char_indices().enumerate() {
+        if c.is_whitespace() || c == '|' {
+            if let Some((char_start, byte_start)) = start.take() {
+                tokens.push((char_start, &input[byte_start..byte_index]));
+            }
+            if c == '|' {
+                tokens.push((char_index, &input[byte_index..byte_index + 1]));
+            }
+        } else if start.is_none() {
+            start = Some((char_index, byte_index));
+        }
+    }
+    if let Some((char_start, byte_start)) = start {
+        tokens.push((char_start, &input[byte_start..]));
+    }
+
+    tokens
+}
@@ -0,0 +1,37 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error for phoneme input containing symbols with no Misaki mapping.
+#[derive(Debug, Clone)]
+pub struct PhonemeConversionError {
+    /// Input notation ("IPA", "ARPAbet" or "X-SAMPA")
+    pub notation: &'static str,
+    pub input: String,
+    /// Each unconvertible symbol with its character offset in `input`
+    pub symbols: Vec<(usize, String)>,
+}
+
+impl fmt::Display for PhonemeConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbols: Vec<String> = self
+            .symbols
+            .iter()
+            .map(|(position, symbol)| {
+                let codepoints: Vec<String> = symbol
+                    .chars()
+                    .map(|c| format!("U+{:04X}", c as u32))
+                    .collect();
+                format!("'{}' ({}) at {}", symbol, codepoints.join(" "), position)
+            })
+            .collect();
+        write!(
+            f,
+            "Cannot convert {} symbol(s) {} in \"{}\"",
+            self.notation,
+            symbols.join(", "),
+            self.input
+        )
+    }
+}
+
+impl Error for PhonemeConversionError {}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Place of articulation, front to back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Place {
+    Bilabial,
+    Labiodental,
+    Dental,
+    Alveolar,
+    Postalveolar,
+    Retroflex,
+    Palatal,
+    Velar,
+    Uvular,
+    Pharyngeal,
+    Glottal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Manner {
+    Plosive,
+    Nasal,
+    Trill,
+    Tap,
+    Fricative,
+    Affricate,
+    Approximant,
+    LateralFricative,
+    LateralApproximant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Features {
+    Consonant {
+        place: Place,
+        manner: Manner,
+        voiced: bool,
+    },
+    Vowel {
+        /// 0 (close) to 6 (open)
+        height: u8,
+        /// 0 (front) to 2 (back)
+        backness: u8,
+        rounded: bool,
+    },
+}
+
+use Manner::*;
+use Place::*;
+
+const fn c(place: Place, manner: Manner, voiced: bool) -> Features {
+    Features::Consonant {
+        place,
+        manner,
+        voiced,
+    }
+}
+
+const fn v(height: u8, backness: u8, rounded: bool) -> Features {
+    Features::Vowel {
+        height,
+        backness,
+        rounded,
+    }
+}
+
+/// Phonetic features of single-character IPA symbols
+#[rustfmt::skip]
+const FEATURES: &[(char, Features)] = &[
+    // Plosives
+    ('p', c(Bilabial, Plosive, false)), ('b', c(Bilabial, Plosive, true)),
+    ('t', c(Alveolar, Plosive, false)), ('d', c(Alveolar, Plosive, true)),
+    ('ʈ', c(Retroflex, Plosive, false)), ('ɖ', c(Retroflex, Plosive, true)),
+    ('c', c(Palatal, Plosive, false)), ('ɟ', c(Palatal, Plosive, true)),
+    ('k', c(Velar, Plosive, false)), ('ɡ', c(Velar, Plosive, true)), ('g', c(Velar, Plosive, true)),
+    ('q', c(Uvular, Plosive, false)), ('ɢ', c(Uvular, Plosive, true)),
+    ('ʔ', c(Glottal, Plosive, false)),
+    // Nasals
+    ('m', c(Bilabial, Nasal, true)), ('ɱ', c(Labiodental, Nasal, true)),
+    ('n', c(Alveolar, Nasal, true)), ('ɳ', c(Retroflex, Nasal, true)),
+    ('ɲ', c(Palatal, Nasal, true)), ('ŋ', c(Velar, Nasal, true)), ('ɴ', c(Uvular, Nasal, true)),
+    // Trills and taps
+    ('ʙ', c(Bilabial, Trill, true)), ('r', c(Alveolar, Trill, true)), ('ʀ', c(Uvular, Trill, true)),
+    ('ⱱ', c(Labiodental, Tap, true)), ('ɾ', c(Alveolar, Tap, true)), ('ɽ', c(Retroflex, Tap, true)),
+    // Fricatives
+    ('ɸ', c(Bilabial, Fricative, false)), ('β', c(Bilabial, Fricative, true)),
+    ('f', c(Labiodental, Fricative, false)), ('v', c(Labiodental, Fricative, true)),
+    ('θ', c(Dental, Fricative, false)), ('ð', c(Dental, Fricative, true)),
+    ('s', c(Alveolar, Fricative, false)), ('z', c(Alveolar, Fricative, true)),
+    ('ʃ', c(Postalveolar, Fricative, false)), ('ʒ', c(Postalveolar, Fricative, true)),
+    ('ɕ', c(Postalveolar, Fricative, false)), ('ʑ', c(Postalveolar, Fricative, true)),
+    ('ʂ', c(Retroflex, Fricative, false)), ('ʐ', c(Retroflex, Fricative, true)),
+    ('ç', c(Palatal, Fricative, false)), ('ʝ', c(Palatal, Fricative, true)),
+    ('x', c(Velar, Fricative, false)), ('ɣ', c(Velar, Fricative, true)),
+    ('χ', c(Uvular, Fricative, false)), ('ʁ', c(Uvular, Fricative, true)),
+    ('ħ', c(Pharyngeal, Fricative, false)), ('ʕ', c(Pharyngeal, Fricative, true)),
+    ('h', c(Glottal, Fricative, false)), ('ɦ', c(Glottal, Fricative, true)),
+    ('ʍ', c(Velar, Fricative, false)),
+    ('ɬ', c(Alveolar, LateralFricative, false)), ('ɮ', c(Alveolar, LateralFricative, true)),
+    // Affricates
+    ('ʦ', c(Alveolar, Affricate, false)), ('ʣ', c(Alveolar, Affricate, true)),
+    ('ʧ', c(Postalveolar, Affricate, false)), ('ʤ', c(Postalveolar, Affricate, true)),
+    ('ʨ', c(Palatal, Affricate, false)), ('ʥ', c(Palatal, Affricate, true)),
+    // Approximants
+    ('ʋ', c(Labiodental, Approximant, true)), ('ɹ', c(Alveolar, Approximant, true)),
+    ('ɻ', c(Retroflex, Approximant, true)), ('j', c(Palatal, Approximant, true)),
+    ('ɥ', c(Palatal, Approximant, true)), ('ɰ', c(Velar, Approximant, true)),
+    ('w', c(Velar, Approximant, true)),
+    ('l', c(Alveolar, LateralApproximant, true)), ('ɫ', c(Alveolar, LateralApproximant, true)),
+    ('ɭ', c(Retroflex, LateralApproximant, true)), ('ʎ', c(Palatal, LateralApproximant, true)),
+    ('ʟ', c(Velar, LateralApproximant, true)),
+    // Vowels
+    ('i', v(0, 0, false)), ('y', v(0, 0, true)), ('ɨ', v(0, 1, false)), ('ʉ', v(0, 1, true)),
+    ('ɯ', v(0, 2, false)), ('u', v(0, 2, true)),
+    ('ɪ', v(1, 0, false)), ('ʏ', v(1, 0, true)), ('ᵻ', v(1, 1, false)), ('ʊ', v(1, 2, true)),
+    ('e', v(2, 0, false)), ('ø', v(2, 0, true)), ('ɘ', v(2, 1, false)), ('ɵ', v(2, 1, true)),
+    ('ɤ', v(2, 2, false)), ('o', v(2, 2, true)),
+    ('ə', v(3, 1, false)), ('ᵊ', v(3, 1, false)), ('ɚ', v(3, 1, false)),
+    ('ɛ', v(4, 0, false)), ('œ', v(4, 0, true)), ('ɜ', v(4, 1, false)), ('ɝ', v(4, 1, false)),
+    ('ɞ', v(4, 1, true)), ('ʌ', v(4, 2, false)), ('ɔ', v(4, 2, true)),
+    ('æ', v(5, 0, false)), ('ɐ', v(5, 1, false)),
+    ('a', v(6, 0, false)), ('ɶ', v(6, 0, true)), ('ɑ', v(6, 2, false)), ('ɒ', v(6, 2, true)),
+];
+
+/// How a symbol missing from the vocab was handled during tokenization
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhonemeSubstitution {
+    /// Character offset of the symbol in the phoneme string
+    pub position: usize,
+    pub original: char,
+    /// Closest vocab symbol, or `None` when the symbol was dropped
+    pub replacement: Option<char>,
+}
+
+impl fmt::Display for PhonemeSubstitution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.replacement {
+            Some(replacement) => write!(
+                f,
+                "'{}' (U+{:04X}) at {} replaced with '{}'",
+                self.original, self.original as u32, self.position, replacement
+            ),
+            None => write!(
+                f,
+                "'{}' (U+{:04X}) at {} dropped",
+                self.original, self.original as u32, self.position
+            ),
+        }
+    }
+}
+
+/// Maps symbols missing from a tokenizer vocab to the phonetically closest
+/// vocab symbol, comparing place, manner and voicing for consonants and
+/// height, backness and rounding for vowels.
+#[derive(Clone)]
+pub struct PhonemeFallback {
+    table: HashMap<char, char>,
+}
+
+impl PhonemeFallback {
+    /// Build the fallback table for every featured symbol not in `vocab`.
+    pub fn new<'a>(vocab: impl IntoIterator<Item = &'a str>) -> Self {
+        let symbols: Vec<char> = vocab
+            .into_iter()
+            .filter_map(|token| {
+                let mut chars = token.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(c),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        let candidates: Vec<(char, Features)> = FEATURES
+            .iter()
+            .filter(|(symbol, _)| symbols.contains(symbol))
+            .copied()
+            .collect();
+
+        let table = FEATURES
+            .iter()
+            .filter(|(symbol, _)| !symbols.contains(symbol))
+            .filter_map(|&(symbol, features)| {
+                candidates
+                    .iter()
+                    .filter_map(|&(candidate, other)| {
+                        distance(features, other).map(|d| (d, candidate))
+                    })
+                    .min_by_key(|&(d, _)| d)
+                    .map(|(_, candidate)| (symbol, candidate))
+            })
+            .collect();
+
+        PhonemeFallback { table }
+    }
+
+    /// Closest vocab symbol for an out-of-vocabulary symbol, if it has one.
+    pub fn closest(&self, symbol: char) -> Option<char> {
+        self.table.get(&symbol).copied()
+    }
+}
+
+/// Weighted feature distance; `None` between a consonant and a vowel
+fn distance(a: Features, b: Features) -> Option<u32> {
+    match (a, b) {
+        (
+            Features::Consonant {
+                place: p1,
+                manner: m1,
+                voiced: v1,
+            },
+            Features::Consonant {
+                place: p2,
+                manner: m2,
+                voiced: v2,
+            },
+        ) => {
+            // Rhotics stand in for each other regardless of place (ʁ -> ɹ,
+            // not ɣ), preferring the approximant the model knows best
+            if is_rhotic(p1, m1) && is_rhotic(p2, m2) {
+                return Some(u32::from(m2 != Approximant) + u32::from(v1 != v2));
+            }
+            Some(
+                (p1 as i32 - p2 as i32).unsigned_abs()
+                    + 3 * manner_distance(m1, m2)
+                    + u32::from(v1 != v2),
+            )
+        }
+        (
+            Features::Vowel {
+                height: h1,
+                backness: b1,
+                rounded: r1,
+            },
+            Features::Vowel {
+                height: h2,
+                backness: b2,
+                rounded: r2,
+            },
+        ) => Some(u32::from(h1.abs_diff(h2)) + u32::from(b1.abs_diff(b2)) + u32::from(r1 != r2)),
+        _ => None,
+    }
+}
+
+fn is_rhotic(place: Place, manner: Manner) -> bool {
+    (matches!(manner, Trill | Tap) && !matches!(place, Bilabial | Labiodental))
+        || (manner == Approximant && matches!(place, Alveolar | Retroflex))
+        // Uvular fricatives are the rhotic of French and German
+        || (manner == Fricative && place == Uvular)
+}
+
+fn manner_distance(a: Manner, b: Manner) -> u32 {
+    if a == b {
+        0
+    } else if matches!(
+        (a, b),
+        (Fricative, Affricate)
+            | (Affricate, Fricative)
+            | (Plosive, Affricate)
+            | (Affricate, Plosive)
+            | (LateralFricative, LateralApproximant)
+            | (LateralApproximant, LateralFricative)
+            | (LateralApproximant, Approximant)
+            | (Approximant, LateralApproximant)
+    ) {
+        1
+    } else {
+        2
+    }
+}
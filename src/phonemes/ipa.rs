@@ -0,0 +1,191 @@
+use super::PhonemeConversionError;
+
+/// Misaki (American English) symbols accepted unchanged from IPA input
+const MISAKI_SYMBOLS: &str = "bdfhijklmnpstuvwzæðŋɑɔəɛɡɪɹɾʃʊʌʒʤʧθᵊᵻˈˌ";
+
+/// Punctuation and spacing passed through to the tokenizer
+const PUNCTUATION: &str = " ;:,.!?—…\"()“”";
+
+/// General IPA sequences mapped to Misaki, matched longest first. This extends
+/// the espeak-specific `FROM_ESPEAKS` rules to the transcriptions found in
+/// dictionaries (Wiktionary, Cambridge, etc.), where diphthongs and affricates
+/// may or may not carry tie bars and British vowels are common.
+const IPA_TO_MISAKI: &[(&str, &str)] = &[
+    // Triphthongs and diphthongs
+    ("aɪə", "Iə"),
+    ("aʊə", "Wə"),
+    ("eɪ", "A"),
+    ("aɪ", "I"),
+    ("ɑɪ", "I"),
+    ("aʊ", "W"),
+    ("ɑʊ", "W"),
+    ("ɔɪ", "Y"),
+    ("oɪ", "Y"),
+    ("oʊ", "O"),
+    ("əʊ", "O"),
+    ("ɪə", "iə"),
+    ("ɛə", "ɛɹ"),
+    ("eə", "ɛɹ"),
+    ("ʊə", "ʊɹ"),
+    // Affricates
+    ("tʃ", "ʧ"),
+    ("dʒ", "ʤ"),
+    ("ʦ", "ts"),
+    ("ʣ", "dz"),
+    // R-colored vowels
+    ("ɜɹ", "ɜɹ"),
+    ("ɜr", "ɜɹ"),
+    ("ɜ", "ɜɹ"),
+    ("ɝ", "ɜɹ"),
+    ("ɚ", "əɹ"),
+    ("˞", "ɹ"),
+    // Glottal stop before a syllabic n, as in "button"
+    ("ʔn", "tᵊn"),
+    // Vowels outside the American Misaki inventory
+    ("ɐ", "ə"),
+    ("ɒ", "ɑ"),
+    ("a", "æ"),
+    ("e", "ɛ"),
+    ("o", "O"),
+    ("ɵ", "O"),
+    ("ʉ", "u"),
+    ("ɨ", "ᵻ"),
+    // Close front rounded, heard as /u/ in English loans ("déjà vu")
+    ("y", "u"),
+    ("ʏ", "ʊ"),
+    // Consonants outside the American Misaki inventory
+    ("g", "ɡ"),
+    ("r", "ɹ"),
+    ("ɻ", "ɹ"),
+    ("ʁ", "ɹ"),
+    ("ʀ", "ɹ"),
+    ("ɫ", "l"),
+    ("ɬ", "l"),
+    ("x", "k"),
+    ("ç", "k"),
+    ("ʔ", "t"),
+    ("ʍ", "w"),
+    ("ɦ", "h"),
+    ("ɱ", "m"),
+    ("ɲ", "nj"),
+    // ASCII stress substitutes
+    ("'", "ˈ"),
+];
+
+/// Marks dropped before conversion: tie bars, length marks, transcription
+/// delimiters, syllable breaks and diacritics with no Misaki counterpart.
+/// A '.' is only a syllable break inside a word; see `is_ignored`.
+#[rustfmt::skip]
+const IGNORED: &[char] = &[
+    '\u{0361}', // combining double inverted breve (tie bar)
+    '\u{035C}', // combining double breve below (tie bar)
+    'ː', 'ˑ', '/', '[', ']', '‿', '|', '‖',
+    '\u{0303}', // nasalized
+    '\u{0325}', // voiceless (ring below)
+    '\u{030A}', // voiceless (ring above)
+    '\u{032A}', // dental
+    '\u{031A}', // no audible release
+    '\u{0320}', // retracted
+    '\u{031F}', // advanced
+    '\u{031D}', // raised
+    '\u{031E}', // lowered
+    '\u{0308}', // centralized
+    '\u{0306}', // extra short
+    '\u{032F}', // non-syllabic
+    'ʰ', 'ʷ', 'ʲ', 'ˠ', 'ˤ',
+];
+
+/// Syllabic consonant marks, rewritten as ᵊ before the consonant
+const SYLLABIC_MARKS: &[char] = &['\u{0329}', '\u{030D}'];
+
+/// Convert general IPA (e.g. pasted from Wiktionary) to Misaki phonemes.
+pub fn ipa_to_misaki(ipa: &str) -> Result<String, PhonemeConversionError> {
+    // Keep the original character offset of every symbol for error reporting
+    let mut chars: Vec<(usize, char)> = Vec::with_capacity(ipa.len());
+    let source: Vec<char> = ipa.chars().collect();
+    for (index, &c) in source.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|i| source[i]);
+        if !is_ignored(c, previous, source.get(index + 1).copied()) {
+            chars.push((index, c));
+        }
+    }
+
+    let max_rule_chars = IPA_TO_MISAKI
+        .iter()
+        .map(|(from, _)| from.chars().count())
+        .max()
+        .unwrap_or(1);
+
+    let mut result = String::with_capacity(ipa.len());
+    let mut unknown = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (position, c) = chars[i];
+
+        if SYLLABIC_MARKS.contains(&c) {
+            // A syllabic mark with nothing to attach to
+            i += 1;
+            continue;
+        }
+
+        let (replacement, consumed) = match longest_rule(&chars[i..], max_rule_chars) {
+            Some(rule) => rule,
+            None if MISAKI_SYMBOLS.contains(c) || PUNCTUATION.contains(c) => (c.to_string(), 1),
+            None if c.is_whitespace() => (" ".to_string(), 1),
+            None => {
+                unknown.push((position, c.to_string()));
+                i += 1;
+                continue;
+            }
+        };
+        i += consumed;
+
+        if i < chars.len() && SYLLABIC_MARKS.contains(&chars[i].1) {
+            // Rules such as ʔn -> tᵊn already produce the syllabic schwa
+            if !replacement.contains('ᵊ') {
+                result.push('ᵊ');
+            }
+            i += 1;
+        }
+        result.push_str(&replacement);
+    }
+
+    if !unknown.is_empty() {
+        return Err(PhonemeConversionError {
+            notation: "IPA",
+            input: ipa.to_string(),
+            symbols: unknown,
+        });
+    }
+
+    Ok(result.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn longest_rule(chars: &[(usize, char)], max_rule_chars: usize) -> Option<(String, usize)> {
+    let limit = max_rule_chars.min(chars.len());
+    for len in (1..=limit).rev() {
+        let candidate: String = chars[..len].iter().map(|&(_, c)| c).collect();
+        if let Some((_, to)) = IPA_TO_MISAKI.iter().find(|(from, _)| *from == candidate) {
+            return Some((to.to_string(), len));
+        }
+    }
+    None
+}
+
+/// ASCII ':' is a length mark after a phoneme and punctuation otherwise;
+/// '.' is a syllable break between symbols and sentence punctuation
+/// otherwise (kept, since Kokoro's vocab has it).
+fn is_ignored(c: char, previous: Option<char>, next: Option<char>) -> bool {
+    match c {
+        ':' => previous.map(|p| p.is_alphabetic()).unwrap_or(false),
+        '.' => {
+            let in_word = |c: Option<char>| {
+                c.map(|c| !c.is_whitespace() && !PUNCTUATION.contains(c) && !IGNORED.contains(&c))
+                    .unwrap_or(false)
+            };
+            in_word(previous) && in_word(next)
+        }
+        _ => IGNORED.contains(&c),
+    }
+}
@@ -0,0 +1,13 @@
+pub mod arpabet;
+pub mod error;
+pub mod fallback;
+pub mod ipa;
+pub mod stress;
+pub mod xsampa;
+
+pub use arpabet::arpabet_to_misaki;
+pub use error::PhonemeConversionError;
+pub use fallback::{PhonemeFallback, PhonemeSubstitution};
+pub use ipa::ipa_to_misaki;
+pub use stress::{StressNormalizer, DEFAULT_FUNCTION_WORDS};
+pub use xsampa::xsampa_to_misaki;
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+
+/// Function words Misaki leaves unstressed in running speech
+pub const DEFAULT_FUNCTION_WORDS: &[&str] = &[
+    "a", "am", "an", "and", "are", "as", "at", "be", "been", "but", "by", "can", "could", "did",
+    "do", "does", "for", "from", "had", "has", "have", "he", "her", "him", "his", "if", "in", "is",
+    "it", "its", "me", "my", "of", "on", "or", "our", "shall", "she", "should", "than", "that",
+    "the", "their", "them", "they", "to", "us", "was", "we", "were", "will", "with", "would",
+    "you", "your",
+];
+
+const PRIMARY_STRESS: char = 'ˈ';
+const SECONDARY_STRESS: char = 'ˌ';
+
+/// Misaki vowels, including the capital letters used for diphthongs
+const VOWELS: &str = "AIOQWYaeiouæɐɑɒɔəɘɚɛɜɝɞɨɪɵʉʊʌᵊᵻ";
+
+/// Rewrites stress in Misaki phonemes produced from espeak-ng output so it
+/// matches the Python pipeline: stress marks sit right before the stressed
+/// vowel, and function words carry no stress.
+#[derive(Clone, Default)]
+pub struct StressNormalizer {
+    /// Lowercase function words
+    function_words: HashSet<String>,
+}
+
+impl StressNormalizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_function_word(&mut self, word: &str) {
+        self.function_words.insert(word.to_lowercase());
+    }
+
+    /// Normalize the phonemes of `text`. Function words are found among the
+    /// words of `text` and unstressed in the phoneme word at the same
+    /// position, whatever form the G2P backend gave them in context; a
+    /// homophone such as "two" keeps its stress. When the phonemes don't
+    /// split into one word per text word (numbers, words the backend merged),
+    /// only stress placement is normalized.
+    pub fn normalize(&self, text: &str, phonemes: &str) -> String {
+        let moved = move_stress_to_vowels(phonemes);
+
+        let words: Vec<String> = text
+            .split_whitespace()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\''))
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        let phoneme_words = moved.split(' ').filter(|word| word.chars().any(is_phoneme));
+        if phoneme_words.count() != words.len() {
+            return moved;
+        }
+
+        let mut words = words.iter();
+        moved
+            .split(' ')
+            .map(|word| {
+                if !word.chars().any(is_phoneme) {
+                    return word.to_string();
+                }
+                match words.next() {
+                    Some(source) if self.function_words.contains(source) => strip_stress(word),
+                    _ => word.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Move each stress mark from the start of its syllable (where espeak puts it)
+/// to directly before the syllable's vowel, as Misaki does. A mark with no
+/// vowel after it in the same word is dropped.
+pub fn move_stress_to_vowels(phonemes: &str) -> String {
+    let mut result = String::with_capacity(phonemes.len());
+    let mut pending: Option<char> = None;
+
+    for c in phonemes.chars() {
+        if c == PRIMARY_STRESS || c == SECONDARY_STRESS {
+            // Keep the stronger mark if two meet before a vowel
+            if pending != Some(PRIMARY_STRESS) {
+                pending = Some(c);
+            }
+            continue;
+        }
+
+        if VOWELS.contains(c) {
+            if let Some(mark) = pending.take() {
+                result.push(mark);
+            }
+        } else if !is_phoneme(c) {
+            pending = None;
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+fn strip_stress(phonemes: &str) -> String {
+    phonemes
+        .chars()
+        .filter(|&c| c != PRIMARY_STRESS && c != SECONDARY_STRESS)
+        .collect()
+}
+
+/// Anything except word separators and punctuation
+fn is_phoneme(c: char) -> bool {
+    !c.is_whitespace() && !";:,.!?—…\"()“”".contains(c)
+}
@@ -0,0 +1,169 @@
+use super::{ipa_to_misaki, PhonemeConversionError};
+
+/// X-SAMPA symbols mapped to IPA, matched longest first. Only symbols with a
+/// counterpart in `ipa_to_misaki` are listed; anything else is reported.
+const XSAMPA_TO_IPA: &[(&str, &str)] = &[
+    // Affricates
+    ("tS", "tʃ"),
+    ("dZ", "dʒ"),
+    ("ts", "ts"),
+    ("dz", "dz"),
+    // Vowels
+    ("@`", "ɚ"),
+    ("3`", "ɝ"),
+    ("{", "æ"),
+    ("@", "ə"),
+    ("6", "ɐ"),
+    ("3", "ɜ"),
+    ("A", "ɑ"),
+    ("Q", "ɒ"),
+    ("O", "ɔ"),
+    ("E", "ɛ"),
+    ("I", "ɪ"),
+    ("U", "ʊ"),
+    ("V", "ʌ"),
+    ("1", "ɨ"),
+    ("}", "ʉ"),
+    ("8", "ɵ"),
+    ("a", "a"),
+    ("e", "e"),
+    ("i", "i"),
+    ("o", "o"),
+    ("u", "u"),
+    // Consonants
+    ("r\\", "ɹ"),
+    ("R\\", "ʀ"),
+    ("r`", "ɻ"),
+    ("h\\", "ɦ"),
+    ("K", "ɬ"),
+    ("5", "ɫ"),
+    ("4", "ɾ"),
+    ("D", "ð"),
+    ("T", "θ"),
+    ("S", "ʃ"),
+    ("Z", "ʒ"),
+    ("N", "ŋ"),
+    ("J", "ɲ"),
+    ("F", "ɱ"),
+    ("R", "ʁ"),
+    ("W", "ʍ"),
+    ("C", "ç"),
+    ("?", "ʔ"),
+    ("b", "b"),
+    ("d", "d"),
+    ("f", "f"),
+    ("g", "ɡ"),
+    ("h", "h"),
+    ("j", "j"),
+    ("k", "k"),
+    ("l", "l"),
+    ("m", "m"),
+    ("n", "n"),
+    ("p", "p"),
+    ("r", "r"),
+    ("s", "s"),
+    ("t", "t"),
+    ("v", "v"),
+    ("w", "w"),
+    ("x", "x"),
+    ("z", "z"),
+    // Suprasegmentals and diacritics
+    ("\"", "ˈ"),
+    ("%", "ˌ"),
+    (":", "ː"),
+    ("=", "\u{0329}"),
+    ("_h", "ʰ"),
+    ("_w", "ʷ"),
+    ("_j", "ʲ"),
+    ("~", "\u{0303}"),
+    ("_0", "\u{0325}"),
+    ("_~", "\u{0303}"),
+    ("_^", "\u{032F}"),
+    ("_d", "\u{032A}"),
+    ("_}", "\u{031A}"),
+    ("-", ""),
+    (".", "."),
+];
+
+/// Punctuation passed through unchanged (`.` is a syllable break in X-SAMPA)
+const PUNCTUATION: &str = ",;!—…()";
+
+/// Convert X-SAMPA (e.g. `h@"l@U`) to Misaki phonemes.
+///
+/// The input is transliterated to IPA and then converted with `ipa_to_misaki`,
+/// so the same vowel and consonant folding applies. Errors point at the
+/// offending X-SAMPA symbols, not the intermediate IPA.
+pub fn xsampa_to_misaki(xsampa: &str) -> Result<String, PhonemeConversionError> {
+    let chars: Vec<char> = xsampa.chars().collect();
+    let max_rule_chars = XSAMPA_TO_IPA
+        .iter()
+        .map(|(from, _)| from.chars().count())
+        .max()
+        .unwrap_or(1);
+
+    let mut ipa = String::with_capacity(xsampa.len());
+    // X-SAMPA offset of the symbol each IPA character came from
+    let mut origins: Vec<usize> = Vec::with_capacity(xsampa.len());
+    let mut unknown = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        let (replacement, consumed) = match longest_rule(&chars[i..], max_rule_chars) {
+            Some(rule) => rule,
+            None if c.is_whitespace() || PUNCTUATION.contains(c) => (c.to_string(), 1),
+            None => {
+                unknown.push((i, c.to_string()));
+                i += 1;
+                continue;
+            }
+        };
+
+        for ipa_char in replacement.chars() {
+            ipa.push(ipa_char);
+            origins.push(i);
+        }
+        i += consumed;
+    }
+
+    if !unknown.is_empty() {
+        return Err(PhonemeConversionError {
+            notation: "X-SAMPA",
+            input: xsampa.to_string(),
+            symbols: unknown,
+        });
+    }
+
+    ipa_to_misaki(&ipa).map_err(|error| PhonemeConversionError {
+        notation: "X-SAMPA",
+        input: xsampa.to_string(),
+        symbols: error
+            .symbols
+            .into_iter()
+            .map(|(position, _)| {
+                let origin = origins[position];
+                (origin, source_symbol(&chars, origin, max_rule_chars))
+            })
+            .collect(),
+    })
+}
+
+fn longest_rule(chars: &[char], max_rule_chars: usize) -> Option<(String, usize)> {
+    let limit = max_rule_chars.min(chars.len());
+    for len in (1..=limit).rev() {
+        let candidate: String = chars[..len].iter().collect();
+        if let Some((_, to)) = XSAMPA_TO_IPA.iter().find(|(from, _)| *from == candidate) {
+            return Some((to.to_string(), len));
+        }
+    }
+    None
+}
+
+/// The X-SAMPA symbol starting at `offset`, as matched during transliteration
+fn source_symbol(chars: &[char], offset: usize, max_rule_chars: usize) -> String {
+    match longest_rule(&chars[offset..], max_rule_chars) {
+        Some((_, len)) => chars[offset..offset + len].iter().collect(),
+        None => chars[offset].to_string(),
+    }
+}
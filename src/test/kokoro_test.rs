@@ -13,11 +13,13 @@ pub fn run_kokoro_with_text(text: &str, no_play: bool) -> Result<(), Box<dyn std
         .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
         .with_max_tokens_length(512)
         .with_sample_rate(24000)
+        // CoreML on Apple hardware, the default CPU provider elsewhere
         .with_execution_providers(vec![
             ort::ExecutionProvider::CoreML(CoreMLExecutionProviderOptions::default())
         ]);
 
     let tts = KokoroTTS::with_config(tts_config)?;
+    println!("Execution provider: {}", tts.execution_provider());
 
     let voice = load_voice_style("models/kokoro/af.bin")?;
 
@@ -1,8 +1,33 @@
 pub mod analyze_ipa;
 pub mod identity_test;
 pub mod kokoro_test;
+#[cfg(feature = "async")]
+pub mod test_async;
+pub mod test_backends;
+pub mod test_batch;
+pub mod test_cmudict;
+pub mod test_concurrency;
 pub mod test_direct_phonemes;
+pub mod test_errors;
+#[cfg(feature = "espeak")]
 pub mod test_espeak;
 pub mod test_espeak_tokenizer;
+pub mod test_from_memory;
+pub mod test_homographs;
+pub mod test_ipa_input;
+pub mod test_japanese;
 pub mod test_misaki_conversion;
+pub mod test_model_cache;
+pub mod test_model_variants;
+pub mod test_multilingual;
+pub mod test_notation_input;
+pub mod test_phoneme_fallback;
+pub mod test_phonemize;
+pub mod test_pool;
+pub mod test_providers;
 pub mod test_raw_tokens;
+pub mod test_runtime;
+pub mod test_session_options;
+pub mod test_stress;
+pub mod test_synthesis_options;
+pub mod test_synthesis_report;
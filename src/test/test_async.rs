@@ -0,0 +1,136 @@
+use crate::kokoro::{load_voice_style, AsyncKokoroTTS, KokoroTTS, SynthesisOptions, TTSConfig};
+use futures_core::Stream;
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Instant;
+
+/// Minimal executor so the test binary doesn't need a tokio runtime
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+pub fn test_async() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Async Facade ===\n");
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000);
+
+    let tts = AsyncKokoroTTS::new(KokoroTTS::with_config(tts_config)?);
+    let voice_style = Arc::new(load_voice_style("models/kokoro/af.bin")?);
+
+    // Several requests in flight at once on the inference threads
+    let start = Instant::now();
+    let speech = tts.generate_speech("Hello from the async API.", Arc::clone(&voice_style), 1.0);
+    let phonemes = tts.generate_speech_from_phonemes("hɛlˈO wˈɜɹld", Arc::clone(&voice_style), 1.0);
+    let with_options = tts.generate_speech_with_options(
+        "Two chunks. With progress.",
+        Arc::clone(&voice_style),
+        1.0,
+        SynthesisOptions::new().with_progress(|progress| {
+            println!("  chunk {}/{}", progress.chunks_done, progress.chunks_total)
+        }),
+    );
+    let tokens = tts.engine().tokenize("Token input")?;
+    let from_tokens = tts.generate_from_tokens(tokens, Arc::clone(&voice_style), 1.0);
+    let token_count = tts.run(|engine| Ok(engine.tokenize("Counting tokens")?.len()));
+
+    for (name, result) in [
+        ("generate_speech", block_on(speech)),
+        ("generate_speech_with_options", block_on(with_options)),
+        ("generate_speech_from_phonemes", block_on(phonemes)),
+        ("generate_from_tokens", block_on(from_tokens)),
+    ] {
+        match result {
+            Ok(audio) => println!(
+                "✓ {} generated {:.2}s of audio",
+                name, audio.duration_seconds
+            ),
+            Err(e) => println!("✗ {} failed: {}", name, e),
+        }
+    }
+    println!("✓ run returned {} tokens", block_on(token_count)?);
+    println!("Elapsed: {:.2}s\n", start.elapsed().as_secs_f32());
+
+    // Streaming, consumed through both `next` and the Stream trait
+    let text = "This is the first sentence. Here is the second one! And a third?";
+    let mut stream = tts.stream_speech(text, Arc::clone(&voice_style), 1.0);
+    let mut chunks = 0;
+    if let Some(first) = block_on(stream.next()) {
+        println!("✓ First chunk after {:.2}s", first?.duration_seconds);
+        chunks += 1;
+    }
+    while let Some(chunk) = block_on(std::future::poll_fn(|cx| {
+        Pin::new(&mut stream).poll_next(cx)
+    })) {
+        println!("✓ Chunk of {:.2}s", chunk?.duration_seconds);
+        chunks += 1;
+    }
+    println!("Received {} chunks\n", chunks);
+
+    // Dropping futures and streams cancels work that hasn't started
+    let single_thread = AsyncKokoroTTS::with_threads(Arc::clone(tts.engine()), 1);
+    let busy = single_thread.generate_speech(
+        "Keep the only inference thread busy for a moment.",
+        Arc::clone(&voice_style),
+        1.0,
+    );
+    let cancelled = single_thread.run(|_| -> crate::error::Result<()> {
+        println!("✗ Cancelled request ran");
+        Ok(())
+    });
+    drop(cancelled);
+    let stream = single_thread.stream_speech(text, Arc::clone(&voice_style), 1.0);
+    drop(stream);
+    block_on(busy)?;
+    block_on(single_thread.run(|_| Ok(())))?;
+    println!("✓ Dropped requests were skipped");
+
+    // A stream nobody reads must not keep the only thread from other requests
+    let mut unread = single_thread.stream_speech(
+        "One sentence. Another sentence. A third sentence. And a fourth.",
+        Arc::clone(&voice_style),
+        1.0,
+    );
+    let audio = block_on(single_thread.generate_speech(
+        "Served while the stream waits.",
+        Arc::clone(&voice_style),
+        1.0,
+    ))?;
+    println!(
+        "✓ Request finished beside an unread stream ({:.2}s of audio)",
+        audio.duration_seconds
+    );
+    let mut chunks = 0;
+    while let Some(chunk) = block_on(unread.next()) {
+        chunk?;
+        chunks += 1;
+    }
+    if chunks == 4 {
+        println!("✓ Unread stream resumed and delivered all 4 chunks");
+    } else {
+        println!("✗ Unread stream delivered {} of 4 chunks", chunks);
+    }
+
+    Ok(())
+}
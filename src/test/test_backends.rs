@@ -0,0 +1,71 @@
+use crate::kokoro::{load_voice_style, Backend, KokoroTTS, TTSConfig};
+use std::time::Instant;
+
+pub fn test_backends() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Inference Backends ===\n");
+
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+    let text = "The quick brown fox jumps over the lazy dog.";
+    let config = || {
+        TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+            .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+    };
+
+    let tts = KokoroTTS::with_config(config().with_backend(Backend::Ort))?;
+    let start = Instant::now();
+    let reference = tts.generate_speech(text, &voice_style, 1.0)?;
+    println!(
+        "✓ {} on {}: {} samples in {:.2?}",
+        Backend::Ort,
+        tts.execution_provider(),
+        reference.samples.len(),
+        start.elapsed()
+    );
+    reference.save_to_wav("backend_onnxruntime.wav")?;
+
+    #[cfg(feature = "tract")]
+    {
+        let tts = KokoroTTS::with_config(config().with_backend(Backend::Tract))?;
+        let start = Instant::now();
+        let audio = tts.generate_speech(text, &voice_style, 1.0)?;
+        println!(
+            "✓ {} on {}: {} samples in {:.2?}",
+            Backend::Tract,
+            tts.execution_provider(),
+            audio.samples.len(),
+            start.elapsed()
+        );
+        audio.save_to_wav("backend_tract.wav")?;
+
+        // Same graph and inputs; the vocoder's noise source and float
+        // rounding keep the waveforms from being bit-identical
+        let difference = rms(reference
+            .samples
+            .iter()
+            .zip(&audio.samples)
+            .map(|(a, b)| a - b));
+        let relative = difference / rms(reference.samples.iter().copied()).max(f64::EPSILON);
+        if audio.samples.len() == reference.samples.len() && relative < 0.05 {
+            println!("✓ Outputs match (relative RMS difference {:.4})", relative);
+        } else {
+            println!(
+                "✗ Outputs differ: {} vs {} samples, relative RMS difference {:.4}",
+                audio.samples.len(),
+                reference.samples.len(),
+                relative
+            );
+        }
+    }
+    #[cfg(not(feature = "tract"))]
+    println!("- tract skipped (build with --features tract)");
+
+    Ok(())
+}
+
+#[cfg(feature = "tract")]
+fn rms(samples: impl Iterator<Item = f32>) -> f64 {
+    let (sum, count) = samples.fold((0.0f64, 0usize), |(sum, count), sample| {
+        (sum + f64::from(sample) * f64::from(sample), count + 1)
+    });
+    (sum / count.max(1) as f64).sqrt()
+}
@@ -0,0 +1,49 @@
+use crate::cmudict::{CmuDict, CmuDictG2P};
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+use crate::playback::play_wav_file;
+
+const CMUDICT_PATH: &str = "models/cmudict/cmudict.dict";
+
+pub fn test_cmudict(no_play: bool) -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing CMUdict G2P Backend ===\n");
+
+    let g2p = CmuDictG2P::new(CmuDict::load(CMUDICT_PATH)?);
+
+    // Dictionary words, numbers and out-of-vocabulary words (letter-to-sound rules)
+    let test_cases = [
+        "Hello, world!",
+        "The year 1984 had 366 days.",
+        "Kokoroxide speaks without espeak.",
+        "Blorptastic frindles.",
+    ];
+
+    for text in test_cases {
+        println!("Input text: '{}'", text);
+        match g2p.phonemize(text) {
+            Ok(phonemes) => println!("✓ Misaki phonemes: '{}'", phonemes),
+            Err(e) => eprintln!("✗ {}", e),
+        }
+        println!();
+    }
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000)
+        .with_cmudict(CMUDICT_PATH);
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+
+    let audio = tts.generate_speech(test_cases[2], &voice_style, 1.0)?;
+    println!("✓ Generated {:.2}s of audio", audio.duration_seconds);
+
+    let output_path = "test_cmudict.wav";
+    audio.save_to_wav(output_path)?;
+
+    if !no_play {
+        play_wav_file(output_path)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, Language, TTSConfig};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+const THREADS: usize = 8;
+const ROUNDS: usize = 4;
+
+pub fn test_concurrency() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Concurrent Use of a Shared KokoroTTS ===\n");
+
+    // Language detection makes threads switch the espeak-ng voice between
+    // English and Spanish, which must not leak into other threads
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000)
+        .with_language_detection(true)
+        .with_language_voice(Language::Spanish, "models/kokoro/ef_dora.bin");
+
+    let tts = Arc::new(KokoroTTS::with_config(tts_config)?);
+    let voice_style = Arc::new(load_voice_style("models/kokoro/af.bin")?);
+
+    let sentences = [
+        "The quick brown fox jumps over the lazy dog.",
+        "Bienvenidos a nuestra tienda.",
+        "How can we help you today?",
+        "¿En qué podemos ayudarle hoy?",
+    ];
+
+    // Single-threaded reference results
+    let mut expected = Vec::new();
+    for sentence in &sentences {
+        let phonemes = tts.phonemize(sentence)?;
+        let samples = tts
+            .generate_speech(sentence, &voice_style, 1.0)?
+            .samples
+            .len();
+        expected.push((phonemes, samples));
+    }
+    let expected = Arc::new(expected);
+
+    let start = Instant::now();
+    let handles: Vec<_> = (0..THREADS)
+        .map(|thread_index| {
+            let tts = Arc::clone(&tts);
+            let voice_style = Arc::clone(&voice_style);
+            let expected = Arc::clone(&expected);
+            thread::spawn(move || {
+                let mut mismatches = 0;
+                for round in 0..ROUNDS {
+                    // Offset per thread so different languages run at the same time
+                    let index = (thread_index + round) % sentences.len();
+                    let sentence = sentences[index];
+                    let (expected_phonemes, expected_samples) = &expected[index];
+
+                    let phonemes = tts.phonemize(sentence).map_err(|e| e.to_string())?;
+                    let audio = tts
+                        .generate_speech(sentence, &voice_style, 1.0)
+                        .map_err(|e| e.to_string())?;
+
+                    if &phonemes != expected_phonemes || audio.samples.len() != *expected_samples {
+                        println!(
+                            "✗ Thread {} got '{}' ({} samples) for '{}', expected '{}' ({} samples)",
+                            thread_index,
+                            phonemes,
+                            audio.samples.len(),
+                            sentence,
+                            expected_phonemes,
+                            expected_samples
+                        );
+                        mismatches += 1;
+                    }
+                }
+                Ok::<usize, String>(mismatches)
+            })
+        })
+        .collect();
+
+    let mut mismatches = 0;
+    for handle in handles {
+        match handle.join() {
+            Ok(result) => mismatches += result?,
+            Err(_) => return Err("Worker thread panicked".into()),
+        }
+    }
+    let elapsed = start.elapsed();
+
+    let total = THREADS * ROUNDS;
+    if mismatches == 0 {
+        println!(
+            "✓ {} syntheses on {} threads matched the single-threaded results",
+            total, THREADS
+        );
+    } else {
+        println!("✗ {} of {} syntheses differed", mismatches, total);
+    }
+    println!("Elapsed: {:.2}s", elapsed.as_secs_f32());
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::error::Error;
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig, VoiceStyle};
+
+pub fn test_errors() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Typed Errors ===\n");
+
+    let missing_model =
+        TTSConfig::new("models/kokoro/missing.onnx", "models/kokoro/tokenizer.json");
+    match KokoroTTS::with_config(missing_model) {
+        Err(Error::Io { path, .. }) => {
+            println!("✓ Missing model reported as Io ({})", path.display())
+        }
+        Err(e) => println!("✗ Missing model reported as: {}", e),
+        Ok(_) => println!("✗ Missing model loaded"),
+    }
+
+    match load_voice_style("models/kokoro/missing.bin") {
+        Err(Error::Io { .. }) => println!("✓ Missing voice reported as Io"),
+        Err(e) => println!("✗ Missing voice reported as: {}", e),
+        Ok(_) => println!("✗ Missing voice loaded"),
+    }
+
+    // A file whose size is not a multiple of one style vector
+    let truncated_voice = std::env::temp_dir().join("kokoroxide_truncated_voice.bin");
+    std::fs::write(&truncated_voice, [0u8; 100])?;
+    match load_voice_style(&truncated_voice) {
+        Err(e @ Error::VoiceFormat { .. }) => {
+            println!("✓ Truncated voice reported as VoiceFormat: {}", e)
+        }
+        Err(e) => println!("✗ Truncated voice reported as: {}", e),
+        Ok(_) => println!("✗ Truncated voice loaded"),
+    }
+    std::fs::remove_file(&truncated_voice)?;
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000);
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+
+    match tts.generate_speech_from_ipa("hɛˈloʊ ǂ", &voice_style, 1.0) {
+        Err(Error::PhonemeConversion(e)) => {
+            println!("✓ Unconvertible IPA reported as PhonemeConversion: {}", e)
+        }
+        Err(e) => println!("✗ Unconvertible IPA reported as: {}", e),
+        Ok(_) => println!("✗ Unconvertible IPA synthesized"),
+    }
+
+    // Style vectors narrower than the model's style input
+    let narrow_voice = VoiceStyle::new(vec![0.0; 128 * 16], 128);
+    match tts.generate_speech("Hello.", &narrow_voice, 1.0) {
+        Err(e @ Error::VoiceFormat { .. }) => {
+            println!("✓ Mismatched style width reported as VoiceFormat: {}", e)
+        }
+        Err(e) => println!("✗ Mismatched style width reported as: {}", e),
+        Ok(_) => println!("✗ Mismatched style width synthesized"),
+    }
+
+    Ok(())
+}
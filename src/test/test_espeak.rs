@@ -14,7 +14,7 @@ extern "C" {
     fn espeak_SetVoiceByName(name: *const c_char) -> c_int;
 
     fn espeak_TextToPhonemes(
-        textptr: *const *const c_void,
+        textptr: *mut *const c_void,
         textmode: c_int,
         phonememode: c_int,
     ) -> *const c_char;
@@ -0,0 +1,64 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+use std::collections::HashMap;
+
+pub fn test_from_memory() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing In-Memory Model Loading ===\n");
+
+    let model_bytes = std::fs::read("models/kokoro/kokoro.onnx")?;
+    let tokenizer_json = std::fs::read_to_string("models/kokoro/tokenizer.json")?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+    let text = "This model was loaded from memory.";
+
+    let from_file = KokoroTTS::with_config(
+        TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+            .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable),
+    )?;
+    let expected = from_file.generate_speech(text, &voice_style, 1.0)?;
+
+    let from_slice = KokoroTTS::from_memory(&model_bytes, &tokenizer_json)?;
+    let audio = from_slice.generate_speech(text, &voice_style, 1.0)?;
+    if audio.samples.len() == expected.samples.len() {
+        println!(
+            "✓ from_memory(&[u8], &str): {} samples",
+            audio.samples.len()
+        );
+    } else {
+        println!(
+            "✗ from_memory produced {} samples, file loading produced {}",
+            audio.samples.len(),
+            expected.samples.len()
+        );
+    }
+    audio.save_to_wav("from_memory.wav")?;
+
+    // Owned bytes plus a vocabulary parsed by the caller
+    let tokenizer: serde_json::Value = serde_json::from_str(&tokenizer_json)?;
+    let vocab: HashMap<String, i64> = tokenizer["model"]["vocab"]
+        .as_object()
+        .map(|vocab| {
+            vocab
+                .iter()
+                .filter_map(|(token, id)| Some((token.clone(), id.as_i64()?)))
+                .collect()
+        })
+        .unwrap_or_default();
+    let config = TTSConfig::new("", "")
+        .with_model_bytes(model_bytes)
+        .with_vocab(vocab)
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable);
+    let from_vocab = KokoroTTS::with_config(config)?;
+    let ids = from_vocab.tokenize(text)?;
+    if ids == from_file.tokenize(text)? {
+        println!("✓ with_vocab: {} token ids match tokenizer.json", ids.len());
+    } else {
+        println!("✗ with_vocab produced different token ids");
+    }
+
+    let config = TTSConfig::from_memory(&b"not an onnx model"[..], &tokenizer_json);
+    match KokoroTTS::with_config(config) {
+        Err(e) => println!("✓ Invalid model bytes rejected: {}", e),
+        Ok(_) => println!("✗ Invalid model bytes loaded"),
+    }
+
+    Ok(())
+}
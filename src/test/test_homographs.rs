@@ -0,0 +1,67 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, PartOfSpeech, TTSConfig};
+use crate::text::{segments, HomographResolver, Segment};
+
+pub fn test_homographs() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Homograph Disambiguation ===\n");
+
+    let resolver = HomographResolver::new();
+    let test_cases = [
+        "I will read the book tomorrow.",
+        "She read the book yesterday.",
+        "The book has been read twice.",
+        "Lead is a heavy metal, but they lead the race.",
+        "We live near the venue where they play live music.",
+        "Please record the show for the record.",
+        "He wants to present the present to her.",
+        "I read the report and I signed it.",
+        "So do I. Yes, we record live.",
+    ];
+
+    for text in test_cases {
+        println!("Input text: '{}'", text);
+        let resolved: Vec<String> = segments(text, &resolver.resolve_words(text))
+            .into_iter()
+            .filter_map(|segment| match segment {
+                Segment::Phonemes(phonemes) => Some(phonemes),
+                Segment::Text(_) => None,
+            })
+            .collect();
+        println!("Resolved homographs: {:?}\n", resolved);
+    }
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000)
+        .with_homograph_disambiguation(true)
+        .with_homograph("bass", PartOfSpeech::Noun, "bˈAs")
+        .with_pronunciation("kokoro", "kˈOkəɹO");
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+
+    let text = "She read that Kokoro can record the bass line live.";
+    println!("Input text: '{}'", text);
+
+    std::env::set_var("DEBUG_PHONEMES", "1");
+    let result = tts.generate_speech(text, &voice_style, 1.0);
+    std::env::remove_var("DEBUG_PHONEMES");
+
+    let audio = result?;
+    println!("✓ Generated {:.2}s of audio", audio.duration_seconds);
+    audio.save_to_wav("test_homographs.wav")?;
+
+    let plain = KokoroTTS::with_config(
+        TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+            .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+            .with_homograph_disambiguation(false),
+    )?;
+    let audio = plain.generate_speech(text, &voice_style, 1.0)?;
+    println!(
+        "✓ Generated {:.2}s of audio without disambiguation",
+        audio.duration_seconds
+    );
+    audio.save_to_wav("test_homographs_plain.wav")?;
+
+    Ok(())
+}
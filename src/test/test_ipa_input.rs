@@ -0,0 +1,40 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+
+pub fn test_ipa_input() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Standard IPA Input ===\n");
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000);
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+
+    // Wiktionary-style transcriptions, including British vowels, tie bars and length marks
+    let test_cases = [
+        ("hello world (US)", "/həˈloʊ ˈwɝld/"),
+        ("hello world (UK)", "/həˈləʊ ˈwɜːld/"),
+        ("church", "/t͡ʃɝːt͡ʃ/"),
+        ("button", "[ˈbʌʔn̩]"),
+        ("syllable breaks and a full stop", "/ˈhɛ.loʊ. ˈdeɪ.ʒɑ ˈvy./"),
+        ("unconvertible symbol", "/ħæt/"),
+    ];
+
+    for (index, (description, ipa)) in test_cases.iter().enumerate() {
+        println!("Test: {}", description);
+        println!("Input IPA: '{}'", ipa);
+
+        match tts.generate_speech_from_ipa(ipa, &voice_style, 1.0) {
+            Ok(audio) => {
+                println!("✓ Generated {:.2}s of audio", audio.duration_seconds);
+                audio.save_to_wav(format!("test_ipa_{}.wav", index + 1))?;
+            }
+            Err(e) => eprintln!("✗ {}", e),
+        }
+
+        println!();
+    }
+
+    Ok(())
+}
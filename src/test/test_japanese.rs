@@ -0,0 +1,47 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+use crate::playback::play_wav_file;
+
+pub fn test_japanese(no_play: bool) -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Japanese Kana Phonemizer ===\n");
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000)
+        .with_japanese_dictionary("models/kokoro/ja_readings.tsv");
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/jf_alpha.bin")?;
+
+    let test_cases = [
+        ("Greeting (hiragana)", "こんにちは。"),
+        ("Long vowel and geminate", "コーヒーをいっぱいください。"),
+        ("Moraic nasal assimilation", "しんぶん、さんぽ、りんご"),
+        ("Pitch accent marks", "ハ'シ と ハシ'"),
+        ("Kanji from reading dictionary", "日本語を話します。"),
+    ];
+
+    for (index, (description, text)) in test_cases.iter().enumerate() {
+        println!("Test: {}", description);
+        println!("Input text: '{}'", text);
+
+        let filename = format!("test_japanese_{}.wav", index + 1);
+        match tts.generate_speech_japanese(text, &voice_style, 1.0) {
+            Ok(audio) => {
+                println!("✓ Generated {:.2}s of audio", audio.duration_seconds);
+                audio.save_to_wav(&filename)?;
+
+                if !no_play {
+                    if let Err(e) = play_wav_file(&filename) {
+                        eprintln!("  Playback error: {}", e);
+                    }
+                }
+            }
+            Err(e) => eprintln!("✗ Generation failed: {}", e),
+        }
+
+        println!();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+use std::time::Instant;
+
+pub fn test_model_cache() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Optimized Model Cache ===\n");
+
+    let cache_dir = std::env::temp_dir().join("kokoroxide_model_cache");
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    let cache_dir = cache_dir.to_string_lossy().into_owned();
+
+    let config = || {
+        TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+            .with_graph_optimization_level(ort::GraphOptimizationLevel::Level3)
+            .with_optimized_model_cache(&cache_dir)
+    };
+
+    let start = Instant::now();
+    let first = KokoroTTS::with_config(config())?;
+    let cold = start.elapsed();
+
+    let cached_files: Vec<_> = std::fs::read_dir(&cache_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    match cached_files.as_slice() {
+        [file] if file.ends_with(".onnx") => println!("✓ Cached optimized graph as {}", file),
+        files => println!("✗ Expected one cached graph, found {:?}", files),
+    }
+
+    let start = Instant::now();
+    let second = KokoroTTS::with_config(config())?;
+    let warm = start.elapsed();
+    println!("Cold start: {:.2?}, cached start: {:.2?}", cold, warm);
+    if warm < cold {
+        println!("✓ Cached start was faster");
+    } else {
+        println!("✗ Cached start was not faster");
+    }
+
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+    let text = "Loaded from the optimized model cache.";
+    let expected = first.generate_speech(text, &voice_style, 1.0)?;
+    let audio = second.generate_speech(text, &voice_style, 1.0)?;
+    if audio.samples.len() == expected.samples.len() {
+        println!("✓ Cached model produced {} samples", audio.samples.len());
+    } else {
+        println!(
+            "✗ Cached model produced {} samples, expected {}",
+            audio.samples.len(),
+            expected.samples.len()
+        );
+    }
+    audio.save_to_wav("model_cache.wav")?;
+
+    std::fs::remove_dir_all(&cache_dir)?;
+    Ok(())
+}
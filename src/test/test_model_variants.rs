@@ -0,0 +1,53 @@
+use crate::error::Error;
+use crate::kokoro::{load_voice_style, KokoroTTS, ModelVariant, TTSConfig};
+use std::time::Instant;
+
+pub fn test_model_variants() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Model Variants ===\n");
+
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+    let text = "The quick brown fox jumps over the lazy dog.";
+
+    for variant in ModelVariant::ALL {
+        let tts_config = TTSConfig::new("models/kokoro", "models/kokoro/tokenizer.json")
+            .with_model_variant(variant)
+            .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+            .with_max_tokens_length(512)
+            .with_sample_rate(24000);
+
+        let load_start = Instant::now();
+        let tts = match KokoroTTS::with_config(tts_config) {
+            Ok(tts) => tts,
+            Err(Error::Io { path, .. }) => {
+                println!("- {} skipped ({} not found)", variant, path.display());
+                continue;
+            }
+            Err(e) => {
+                println!("✗ {} failed to load: {}", variant, e);
+                continue;
+            }
+        };
+        let load_time = load_start.elapsed();
+
+        let start = Instant::now();
+        match tts.generate_speech(text, &voice_style, 1.0) {
+            Ok(audio)
+                if !audio.samples.is_empty() && audio.samples.iter().all(|s| s.is_finite()) =>
+            {
+                println!(
+                    "✓ {} ({}): loaded in {:.2?}, {:.2}s of audio in {:.2?}",
+                    variant,
+                    tts.model_variant().file_name(),
+                    load_time,
+                    audio.duration_seconds,
+                    start.elapsed()
+                );
+                audio.save_to_wav(format!("variant_{}.wav", variant))?;
+            }
+            Ok(_) => println!("✗ {} produced empty or non-finite audio", variant),
+            Err(e) => println!("✗ {} failed to synthesize: {}", variant, e),
+        }
+    }
+
+    Ok(())
+}
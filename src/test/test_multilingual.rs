@@ -0,0 +1,36 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, Language, TTSConfig};
+use crate::playback::play_wav_file;
+
+pub fn test_multilingual(no_play: bool) -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Per-Sentence Language Detection ===\n");
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000)
+        .with_language_detection(true)
+        .with_language_voice(Language::Spanish, "models/kokoro/ef_dora.bin");
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+
+    let text = "Welcome to our store. Bienvenidos a nuestra tienda. \
+                How can we help you today? ¿En qué podemos ayudarle hoy?";
+    println!("Input text: '{}'", text);
+
+    std::env::set_var("DEBUG_PHONEMES", "1");
+    let result = tts.generate_speech(text, &voice_style, 1.0);
+    std::env::remove_var("DEBUG_PHONEMES");
+
+    let audio = result?;
+    println!("\n✓ Generated {:.2}s of audio", audio.duration_seconds);
+
+    let output_path = "test_multilingual.wav";
+    audio.save_to_wav(output_path)?;
+
+    if !no_play {
+        play_wav_file(output_path)?;
+    }
+
+    Ok(())
+}
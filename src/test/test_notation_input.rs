@@ -0,0 +1,60 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+
+pub fn test_notation_input() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing ARPAbet and X-SAMPA Input ===\n");
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000);
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+
+    let arpabet_cases = [
+        ("hello world", "HH AH0 L OW1 | W ER1 L D"),
+        (
+            "the nation's capital",
+            "DH AH0 | N EY1 SH AH0 N Z | K AE1 P AH0 T AH0 L .",
+        ),
+        ("unknown phone", "HH AH0 L XX1"),
+    ];
+
+    for (index, (description, arpabet)) in arpabet_cases.iter().enumerate() {
+        println!("Test: {}", description);
+        println!("Input ARPAbet: '{}'", arpabet);
+
+        match tts.generate_speech_from_arpabet(arpabet, &voice_style, 1.0) {
+            Ok(audio) => {
+                println!("✓ Generated {:.2}s of audio", audio.duration_seconds);
+                audio.save_to_wav(format!("test_arpabet_{}.wav", index + 1))?;
+            }
+            Err(e) => eprintln!("✗ {}", e),
+        }
+
+        println!();
+    }
+
+    let xsampa_cases = [
+        ("hello world", "h@\"l@U \"w3:ld"),
+        ("church", "\"tS3`tS"),
+        ("unsupported symbol", "\"hy:"),
+    ];
+
+    for (index, (description, xsampa)) in xsampa_cases.iter().enumerate() {
+        println!("Test: {}", description);
+        println!("Input X-SAMPA: '{}'", xsampa);
+
+        match tts.generate_speech_from_xsampa(xsampa, &voice_style, 1.0) {
+            Ok(audio) => {
+                println!("✓ Generated {:.2}s of audio", audio.duration_seconds);
+                audio.save_to_wav(format!("test_xsampa_{}.wav", index + 1))?;
+            }
+            Err(e) => eprintln!("✗ {}", e),
+        }
+
+        println!();
+    }
+
+    Ok(())
+}
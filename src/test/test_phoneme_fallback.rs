@@ -0,0 +1,57 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+
+pub fn test_phoneme_fallback() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Phoneme Fallback for Out-of-Vocabulary Symbols ===\n");
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000);
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+
+    // (description, phonemes, expected (original, replacement) pairs)
+    let test_cases = [
+        ("dark l", "fˈiɫd", &[('ɫ', Some('l'))][..]),
+        ("lateral fricative", "ɬˈæn", &[('ɬ', Some('l'))][..]),
+        ("voiced glottal fricative", "ɦˈælO", &[('ɦ', Some('h'))][..]),
+        (
+            "combining diacritic",
+            "kˈæ\u{0330}t",
+            &[('\u{0330}', None)][..],
+        ),
+        ("in vocab", "həlˈO", &[][..]),
+    ];
+
+    for (index, (description, phonemes, expected)) in test_cases.iter().enumerate() {
+        println!("Test: {}", description);
+        println!("Input phonemes: '{}'", phonemes);
+
+        match tts.generate_speech_from_phonemes(phonemes, &voice_style, 1.0) {
+            Ok(audio) => {
+                for substitution in &audio.substitutions {
+                    println!("  {}", substitution);
+                }
+
+                let actual: Vec<(char, Option<char>)> = audio
+                    .substitutions
+                    .iter()
+                    .map(|s| (s.original, s.replacement))
+                    .collect();
+                if actual == *expected {
+                    println!("✓ Substitutions match");
+                } else {
+                    println!("✗ Expected {:?}, got {:?}", expected, actual);
+                }
+
+                audio.save_to_wav(format!("test_phoneme_fallback_{}.wav", index + 1))?;
+            }
+            Err(e) => eprintln!("✗ {}", e),
+        }
+
+        println!();
+    }
+
+    Ok(())
+}
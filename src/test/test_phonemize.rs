@@ -0,0 +1,44 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+
+pub fn test_phonemize() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Public Phonemize, Tokenize and Decode ===\n");
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000);
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+
+    let test_sentences = [
+        "Hello world",
+        "I read the book yesterday.",
+        "The quick brown fox jumps over the lazy dog.",
+    ];
+
+    for (index, sentence) in test_sentences.iter().enumerate() {
+        println!("Text: '{}'", sentence);
+
+        let phonemes = tts.phonemize(sentence)?;
+        println!("Phonemes: '{}'", phonemes);
+
+        let tokens = tts.tokenize(sentence)?;
+        println!("Tokens: {:?}", tokens);
+
+        let decoded = tts.decode(&tokens);
+        if decoded == phonemes {
+            println!("✓ Decoded tokens match phonemes");
+        } else {
+            println!("✗ Decoded '{}' differs from phonemes", decoded);
+        }
+
+        let audio = tts.generate_from_tokens(&tokens, &voice_style, 1.0)?;
+        println!("✓ Generated {:.2}s of audio", audio.duration_seconds);
+        audio.save_to_wav(format!("test_phonemize_{}.wav", index + 1))?;
+
+        println!();
+    }
+
+    Ok(())
+}
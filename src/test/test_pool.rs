@@ -0,0 +1,150 @@
+use crate::error::Error;
+use crate::kokoro::{load_voice_style, KokoroPool, SynthesisOptions, TTSConfig};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+const SESSIONS: usize = 2;
+const THREADS: usize = 6;
+
+pub fn test_pool() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing KokoroPool ===\n");
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000);
+
+    let pool = Arc::new(KokoroPool::new(tts_config, SESSIONS)?);
+    let voice_style = Arc::new(load_voice_style("models/kokoro/af.bin")?);
+    println!(
+        "✓ Loaded {} sessions on {}",
+        pool.size(),
+        pool.execution_provider()
+    );
+
+    let phonemes = pool.phonemize("Hello world")?;
+    let tokens = pool.tokenize("Hello world")?;
+    println!(
+        "Phonemes: '{}', decoded: '{}'",
+        phonemes,
+        pool.decode(&tokens)
+    );
+
+    // Every thread runs a different entry point so they all share sessions
+    let start = Instant::now();
+    let handles: Vec<_> = (0..THREADS)
+        .map(|thread_index| {
+            let pool = Arc::clone(&pool);
+            let voice_style = Arc::clone(&voice_style);
+            let tokens = tokens.clone();
+            thread::spawn(move || {
+                let result = match thread_index {
+                    0 => pool.generate_speech("The pool shares one tokenizer.", &voice_style, 1.0),
+                    1 => pool.generate_speech_from_phonemes("hɛlˈO wˈɜɹld", &voice_style, 1.0),
+                    2 => pool.generate_speech_from_ipa("/həˈləʊ ˈwɜːld/", &voice_style, 1.0),
+                    3 => pool.generate_speech_from_arpabet(
+                        "HH AH0 L OW1 | W ER1 L D",
+                        &voice_style,
+                        1.0,
+                    ),
+                    4 => pool.generate_speech_from_xsampa("h@\"l@U w3:ld", &voice_style, 1.0),
+                    _ => pool.generate_from_tokens(&tokens, &voice_style, 1.0),
+                };
+                result
+                    .map(|audio| audio.duration_seconds)
+                    .map_err(|e| e.to_string())
+            })
+        })
+        .collect();
+
+    for (thread_index, handle) in handles.into_iter().enumerate() {
+        match handle.join() {
+            Ok(Ok(seconds)) => println!(
+                "✓ Thread {} generated {:.2}s of audio",
+                thread_index, seconds
+            ),
+            Ok(Err(e)) => println!("✗ Thread {} failed: {}", thread_index, e),
+            Err(_) => println!("✗ Thread {} panicked", thread_index),
+        }
+    }
+    println!("Elapsed: {:.2}s", start.elapsed().as_secs_f32());
+
+    let japanese = pool.generate_speech_japanese("こんにちは", &voice_style, 1.0)?;
+    let spoken = pool.speak("Done.", &voice_style)?;
+    let options = SynthesisOptions::new().with_progress(|progress| {
+        println!("  chunk {}/{}", progress.chunks_done, progress.chunks_total)
+    });
+    let chunked = pool.generate_speech_with_options(
+        "Options work through the pool. One sentence at a time.",
+        &voice_style,
+        1.0,
+        &options,
+    )?;
+    println!(
+        "✓ Japanese {:.2}s, speak {:.2}s, with options {:.2}s",
+        japanese.duration_seconds, spoken.duration_seconds, chunked.duration_seconds
+    );
+
+    if let Err(e) = pool.generate_speech_from_arpabet("QQ1 ZZ", &voice_style, 1.0) {
+        println!("✓ Invalid ARPAbet rejected: {}", e);
+    }
+
+    let stats = pool.stats();
+    println!(
+        "\nStats: {} sessions, {} completed, {} failed, peak queue {}",
+        stats.sessions, stats.completed, stats.failed, stats.peak_queued
+    );
+    if stats.completed as usize == THREADS + 3
+        && stats.failed == 1
+        && stats.busy == 0
+        && stats.queued == 0
+    {
+        println!("✓ All requests completed and sessions returned");
+    } else {
+        println!("✗ Unexpected pool state");
+    }
+    println!(
+        "Average wait {:.1}ms, average latency {:.1}ms, max latency {:.1}ms",
+        stats.average_wait.as_secs_f64() * 1000.0,
+        stats.average_latency.as_secs_f64() * 1000.0,
+        stats.max_latency.as_secs_f64() * 1000.0
+    );
+
+    // Backpressure: one session, no queue, two concurrent requests
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable);
+    let strict_pool = Arc::new(KokoroPool::new(tts_config, 1)?.with_max_queue(0));
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            let pool = Arc::clone(&strict_pool);
+            let voice_style = Arc::clone(&voice_style);
+            thread::spawn(move || {
+                pool.generate_speech(
+                    "A long enough sentence to keep the only session busy for a while.",
+                    &voice_style,
+                    1.0,
+                )
+            })
+        })
+        .collect();
+
+    let mut rejected = 0;
+    for handle in handles {
+        if let Ok(Err(Error::QueueFull { limit })) = handle.join() {
+            println!("✓ Request rejected with QueueFull (limit {})", limit);
+            rejected += 1;
+        }
+    }
+    let stats = strict_pool.stats();
+    if stats.rejected == rejected {
+        println!("✓ Rejections counted: {}", stats.rejected);
+    } else {
+        println!(
+            "✗ Stats report {} rejections, saw {}",
+            stats.rejected, rejected
+        );
+    }
+
+    Ok(())
+}
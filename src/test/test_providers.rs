@@ -0,0 +1,62 @@
+use crate::error::Error;
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+use ort::execution_providers::{
+    CPUExecutionProviderOptions, CUDAExecutionProviderOptions, CoreMLExecutionProviderOptions,
+};
+use ort::ExecutionProvider;
+
+pub fn test_providers() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Execution Provider Fallback ===\n");
+
+    let base_config = || {
+        TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+            .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+    };
+    let chain = || {
+        vec![
+            ExecutionProvider::CUDA(CUDAExecutionProviderOptions::default()),
+            ExecutionProvider::CoreML(CoreMLExecutionProviderOptions::default()),
+            ExecutionProvider::CPU(CPUExecutionProviderOptions::default()),
+        ]
+    };
+
+    let tts = KokoroTTS::with_config(base_config().with_execution_providers(chain()))?;
+    println!(
+        "✓ CUDA → CoreML → CPU chain ran on {}",
+        tts.execution_provider()
+    );
+
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+    let audio = tts.generate_speech("Execution provider fallback.", &voice_style, 1.0)?;
+    println!("✓ Generated {:.2}s of audio", audio.duration_seconds);
+
+    // Strict loading accepts the chain because it ends with CPU
+    match KokoroTTS::with_config(
+        base_config()
+            .with_execution_providers(chain())
+            .with_strict_execution_providers(true),
+    ) {
+        Ok(tts) => println!(
+            "✓ Strict chain ending in CPU loaded on {}",
+            tts.execution_provider()
+        ),
+        Err(e) => println!("✗ Strict chain ending in CPU failed: {}", e),
+    }
+
+    // Without a CPU entry, strict loading fails when no accelerator is usable
+    let accelerators = vec![
+        ExecutionProvider::CUDA(CUDAExecutionProviderOptions::default()),
+        ExecutionProvider::CoreML(CoreMLExecutionProviderOptions::default()),
+    ];
+    match KokoroTTS::with_config(
+        base_config()
+            .with_execution_providers(accelerators)
+            .with_strict_execution_providers(true),
+    ) {
+        Ok(tts) => println!("✓ Accelerator available: {}", tts.execution_provider()),
+        Err(e @ Error::Config { .. }) => println!("✓ Strict loading refused CPU fallback: {}", e),
+        Err(e) => println!("✗ Strict loading failed unexpectedly: {}", e),
+    }
+
+    Ok(())
+}
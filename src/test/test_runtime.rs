@@ -0,0 +1,93 @@
+use crate::error::Error;
+use crate::kokoro::{load_voice_style, KokoroRuntime, ModelVariant, TTSConfig};
+use std::sync::Arc;
+
+pub fn test_runtime() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Multi-Model Runtime ===\n");
+
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+    let text = "One environment, several models.";
+    let config = |variant: ModelVariant| {
+        TTSConfig::new("models/kokoro", "models/kokoro/tokenizer.json")
+            .with_model_variant(variant)
+            .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+    };
+
+    let runtime = KokoroRuntime::new()?;
+    runtime.load(
+        "legacy",
+        TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+            .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+            .with_thread_spinning(false),
+    )?;
+    for variant in [ModelVariant::Full, ModelVariant::Quantized] {
+        let name = variant.to_string();
+        match runtime.load(&name, config(variant)) {
+            Ok(_) => {}
+            Err(Error::Io { path, .. }) => {
+                println!("- {} skipped ({} not found)", name, path.display());
+            }
+            Err(e) => println!("✗ {} failed to load: {}", name, e),
+        }
+    }
+    println!("✓ Loaded models: {}", runtime.models().join(", "));
+
+    for name in runtime.models() {
+        let tts = runtime.get(&name)?;
+        let audio = tts.generate_speech(text, &voice_style, 1.0)?;
+        println!(
+            "✓ {} ({}): {:.2}s of audio",
+            name,
+            tts.model_variant(),
+            audio.duration_seconds
+        );
+        audio.save_to_wav(format!("runtime_{}.wav", name))?;
+    }
+
+    // Handles outlive unloading; the registry forgets the model
+    let held = runtime.get("legacy")?;
+    if runtime.unload("legacy") && !runtime.contains("legacy") && !runtime.unload("legacy") {
+        println!("✓ Unloaded 'legacy'");
+    } else {
+        println!("✗ Unexpected registry state after unload");
+    }
+    match runtime.get("legacy") {
+        Err(e @ Error::Config { .. }) => println!("✓ Unloaded model is gone: {}", e),
+        _ => println!("✗ Unloaded model is still handed out"),
+    }
+    held.generate_speech(text, &voice_style, 1.0)?;
+    println!("✓ Existing handle still synthesizes after unload");
+
+    // Reloading swaps in a fresh engine without touching old handles
+    if let Some(name) = runtime.models().first().cloned() {
+        let before = runtime.get(&name)?;
+        let after = runtime.reload(&name)?;
+        if !Arc::ptr_eq(&before, &after) && Arc::ptr_eq(&after, &runtime.get(&name)?) {
+            println!("✓ Reloaded '{}'", name);
+        } else {
+            println!("✗ Reload did not replace '{}'", name);
+        }
+        before.generate_speech(text, &voice_style, 1.0)?;
+        after.generate_speech(text, &voice_style, 1.0)?;
+        println!("✓ Old and new handles both synthesize");
+    }
+    match runtime.reload("legacy") {
+        Err(Error::Config { .. }) => println!("✓ Reloading an unloaded model fails"),
+        _ => println!("✗ Reloaded a model that was unloaded"),
+    }
+
+    // A second runtime is independent of the first
+    let other = KokoroRuntime::new()?;
+    other.load(
+        "legacy",
+        TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+            .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable),
+    )?;
+    if other.models() == ["legacy"] && !runtime.contains("legacy") {
+        println!("✓ Runtimes keep separate registries");
+    } else {
+        println!("✗ Runtimes share a registry");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+use std::time::Instant;
+
+/// Threads in this process, where the platform reports it
+fn thread_count() -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Threads:"))
+        .and_then(|count| count.trim().parse().ok())
+}
+
+pub fn test_session_options() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Session Threading and Memory Options ===\n");
+
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+    let text = "Threading options keep one engine from taking every core.";
+    let base_config =
+        || TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json");
+
+    let before = thread_count();
+    let quiet = KokoroTTS::with_config(
+        base_config()
+            .with_thread_spinning(false)
+            .with_intra_op_threads(2)
+            .with_inter_op_threads(1),
+    )?;
+    let start = Instant::now();
+    let audio = quiet.generate_speech(text, &voice_style, 1.0)?;
+    println!(
+        "✓ No thread spinning, 2 intra-op threads: {:.2}s of audio in {:.2?}",
+        audio.duration_seconds,
+        start.elapsed()
+    );
+
+    let lean = KokoroTTS::with_config(
+        base_config()
+            .with_intra_op_threads(1)
+            .with_parallel_execution(false)
+            .with_memory_arena(false)
+            .with_memory_pattern(false),
+    )?;
+    let start = Instant::now();
+    let lean_audio = lean.generate_speech(text, &voice_style, 1.0)?;
+    println!(
+        "✓ Sequential, 1 intra-op thread, no arena or memory pattern: {:.2}s of audio in {:.2?}",
+        lean_audio.duration_seconds,
+        start.elapsed()
+    );
+    lean_audio.save_to_wav("session_options.wav")?;
+
+    if let (Some(before), Some(after)) = (before, thread_count()) {
+        println!(
+            "Process threads: {} before loading, {} after",
+            before, after
+        );
+    }
+
+    if audio.samples.len() == lean_audio.samples.len() {
+        println!("✓ Both configurations produced the same length");
+    } else {
+        println!(
+            "✗ Lengths differ: {} vs {} samples",
+            audio.samples.len(),
+            lean_audio.samples.len()
+        );
+    }
+
+    Ok(())
+}
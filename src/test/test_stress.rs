@@ -0,0 +1,66 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+use crate::phonemes::stress::move_stress_to_vowels;
+
+pub fn test_stress() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Misaki Stress Normalization ===\n");
+
+    // espeak places stress before the syllable onset; Misaki before the vowel
+    let placement_cases = [
+        ("hello", "həˈlO", "həlˈO"),
+        ("string", "ˈstɹɪŋ", "stɹˈɪŋ"),
+        ("understand", "ˌʌndɚˈstænd", "ˌʌndɚstˈænd"),
+    ];
+
+    for (word, espeak, expected) in placement_cases {
+        let moved = move_stress_to_vowels(espeak);
+        let status = if moved == expected { "✓" } else { "✗" };
+        println!(
+            "{} {}: '{}' -> '{}' (expected '{}')",
+            status, word, espeak, moved, expected
+        );
+    }
+    println!();
+
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+    let text = "The history of the world is written in the stars, two to four.";
+
+    let configs = [
+        (
+            "raw espeak stress",
+            TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+                .with_stress_normalization(false),
+        ),
+        (
+            "normalized stress",
+            TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+                .with_stress_normalization(true),
+        ),
+        (
+            "custom function words",
+            TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+                .with_stress_normalization(true)
+                .with_function_words(&["the", "of", "in"]),
+        ),
+    ];
+
+    for (index, (description, config)) in configs.into_iter().enumerate() {
+        println!("Test: {}", description);
+
+        let tts = KokoroTTS::with_config(
+            config
+                .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+                .with_max_tokens_length(512)
+                .with_sample_rate(24000),
+        )?;
+
+        std::env::set_var("DEBUG_PHONEMES", "1");
+        let result = tts.generate_speech(text, &voice_style, 1.0);
+        std::env::remove_var("DEBUG_PHONEMES");
+
+        let audio = result?;
+        println!("✓ Generated {:.2}s of audio\n", audio.duration_seconds);
+        audio.save_to_wav(format!("test_stress_{}.wav", index + 1))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::kokoro::{
+    load_voice_style, CancellationToken, KokoroTTS, SynthesisOptions, SynthesisProgress, TTSConfig,
+};
+use std::sync::{Arc, Mutex};
+
+pub fn test_synthesis_options() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Synthesis Cancellation and Progress ===\n");
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(512)
+        .with_sample_rate(24000);
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+
+    let chapter = "It was a bright cold day in April. The clocks were striking thirteen. \
+                   Winston slipped quickly through the glass doors. A swirl of gritty dust \
+                   entered along with him. The hallway smelt of boiled cabbage.";
+
+    // Progress for a full run
+    let updates: Arc<Mutex<Vec<SynthesisProgress>>> = Arc::default();
+    let recorded = Arc::clone(&updates);
+    let options = SynthesisOptions::new().with_progress(move |progress| {
+        println!(
+            "  {}/{} chunks, {:.2}s of audio after {:.2}s",
+            progress.chunks_done,
+            progress.chunks_total,
+            progress.audio_seconds,
+            progress.elapsed.as_secs_f32()
+        );
+        recorded.lock().unwrap().push(progress);
+    });
+
+    let audio = tts.generate_speech_with_options(chapter, &voice_style, 1.0, &options)?;
+    let updates = updates.lock().unwrap();
+    match updates.last() {
+        Some(last) if last.chunks_done == last.chunks_total && !audio.cancelled => {
+            println!(
+                "✓ {} progress updates, {:.2}s of audio",
+                updates.len(),
+                audio.duration_seconds
+            )
+        }
+        _ => println!("✗ Progress did not reach the last chunk"),
+    }
+    audio.save_to_wav("test_synthesis_options_full.wav")?;
+
+    // Cancel from the progress callback after the second chunk
+    let token = CancellationToken::new();
+    let trigger = token.clone();
+    let options = SynthesisOptions::new()
+        .with_cancellation(token)
+        .with_progress(move |progress| {
+            if progress.chunks_done == 2 {
+                trigger.cancel();
+            }
+        });
+
+    let partial = tts.generate_speech_with_options(chapter, &voice_style, 1.0, &options)?;
+    if partial.cancelled && partial.duration_seconds < audio.duration_seconds {
+        println!(
+            "✓ Cancelled job returned {:.2}s of partial audio",
+            partial.duration_seconds
+        );
+    } else {
+        println!("✗ Cancellation did not stop synthesis early");
+    }
+    partial.save_to_wav("test_synthesis_options_partial.wav")?;
+
+    // Cancelled before starting
+    let token = CancellationToken::new();
+    token.cancel();
+    let options = SynthesisOptions::new().with_cancellation(token);
+    let empty = tts.generate_speech_with_options(chapter, &voice_style, 1.0, &options)?;
+    if empty.cancelled && empty.samples.is_empty() {
+        println!("✓ Pre-cancelled job returned no audio");
+    } else {
+        println!("✗ Pre-cancelled job produced audio");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use crate::kokoro::{load_voice_style, KokoroTTS, TTSConfig};
+
+pub fn test_synthesis_report() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Testing Synthesis Reports ===\n");
+
+    let tts_config = TTSConfig::new("models/kokoro/kokoro.onnx", "models/kokoro/tokenizer.json")
+        .with_graph_optimization_level(ort::GraphOptimizationLevel::Disable)
+        .with_max_tokens_length(32)
+        .with_sample_rate(24000)
+        .with_synthesis_report(true);
+
+    let tts = KokoroTTS::with_config(tts_config)?;
+    let voice_style = load_voice_style("models/kokoro/af.bin")?;
+
+    // (description, text, expect truncation with the 32 token limit)
+    let test_cases = [
+        ("short sentence", "Hello world", false),
+        (
+            "truncated sentence",
+            "The quick brown fox jumps over the lazy dog near the riverbank.",
+            true,
+        ),
+    ];
+
+    for (index, (description, text, expect_truncated)) in test_cases.iter().enumerate() {
+        println!("Test: {}", description);
+        println!("Text: '{}'", text);
+
+        let audio = tts.generate_speech(text, &voice_style, 1.0)?;
+        let Some(report) = audio.reports.first() else {
+            println!("✗ No synthesis report attached");
+            continue;
+        };
+
+        println!("{}", report.to_json_string());
+
+        if report.truncated == *expect_truncated {
+            println!(
+                "✓ Truncation reported as {} ({} tokens dropped)",
+                report.truncated, report.dropped_tokens
+            );
+        } else {
+            println!("✗ Expected truncated = {}", expect_truncated);
+        }
+
+        if report.token_ids.len() <= 32 {
+            println!("✓ {} token ids within the limit", report.token_ids.len());
+        } else {
+            println!("✗ {} token ids exceed the limit", report.token_ids.len());
+        }
+
+        audio.save_to_wav(format!("test_synthesis_report_{}.wav", index + 1))?;
+        println!();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,291 @@
+use super::pos::{PartOfSpeech, PosTagger, Upos};
+use std::collections::HashMap;
+
+/// Built-in homographs with Misaki pronunciations per part of speech
+#[rustfmt::skip]
+const HOMOGRAPHS: &[(&str, &[(PartOfSpeech, &str)])] = &[
+    ("read", &[(PartOfSpeech::Verb, "ɹˈid"), (PartOfSpeech::PastTense, "ɹˈɛd")]),
+    ("lead", &[(PartOfSpeech::Verb, "lˈid"), (PartOfSpeech::Noun, "lˈɛd"), (PartOfSpeech::Adjective, "lˈid")]),
+    ("live", &[(PartOfSpeech::Verb, "lˈɪv"), (PartOfSpeech::Adjective, "lˈIv")]),
+    ("lives", &[(PartOfSpeech::Verb, "lˈɪvz"), (PartOfSpeech::Noun, "lˈIvz")]),
+    ("record", &[(PartOfSpeech::Noun, "ɹˈɛkəɹd"), (PartOfSpeech::Verb, "ɹəkˈɔɹd")]),
+    ("present", &[(PartOfSpeech::Noun, "pɹˈɛzᵊnt"), (PartOfSpeech::Verb, "pɹizˈɛnt")]),
+    ("object", &[(PartOfSpeech::Noun, "ˈɑbʤɛkt"), (PartOfSpeech::Verb, "əbʤˈɛkt")]),
+    ("permit", &[(PartOfSpeech::Noun, "pˈɜɹmɪt"), (PartOfSpeech::Verb, "pəɹmˈɪt")]),
+    ("project", &[(PartOfSpeech::Noun, "pɹˈɑʤɛkt"), (PartOfSpeech::Verb, "pɹəʤˈɛkt")]),
+    ("produce", &[(PartOfSpeech::Noun, "pɹˈOdus"), (PartOfSpeech::Verb, "pɹədˈus")]),
+    ("content", &[(PartOfSpeech::Noun, "kˈɑntɛnt"), (PartOfSpeech::Adjective, "kəntˈɛnt")]),
+    ("desert", &[(PartOfSpeech::Noun, "dˈɛzəɹt"), (PartOfSpeech::Verb, "dəzˈɜɹt")]),
+    ("refuse", &[(PartOfSpeech::Noun, "ɹˈɛfjus"), (PartOfSpeech::Verb, "ɹəfjˈuz")]),
+    ("contract", &[(PartOfSpeech::Noun, "kˈɑntɹækt"), (PartOfSpeech::Verb, "kəntɹˈækt")]),
+    ("conduct", &[(PartOfSpeech::Noun, "kˈɑndʌkt"), (PartOfSpeech::Verb, "kəndˈʌkt")]),
+    ("increase", &[(PartOfSpeech::Noun, "ˈɪnkɹis"), (PartOfSpeech::Verb, "ɪnkɹˈis")]),
+    ("decrease", &[(PartOfSpeech::Noun, "dˈikɹis"), (PartOfSpeech::Verb, "dəkɹˈis")]),
+    ("wind", &[(PartOfSpeech::Noun, "wˈɪnd"), (PartOfSpeech::Verb, "wˈInd")]),
+    ("tear", &[(PartOfSpeech::Noun, "tˈɪɹ"), (PartOfSpeech::Verb, "tˈɛɹ")]),
+    ("close", &[(PartOfSpeech::Adjective, "klˈOs"), (PartOfSpeech::Verb, "klˈOz")]),
+    ("use", &[(PartOfSpeech::Noun, "jˈus"), (PartOfSpeech::Verb, "jˈuz")]),
+    ("wound", &[(PartOfSpeech::Noun, "wˈund"), (PartOfSpeech::PastTense, "wˈWnd")]),
+    ("bow", &[(PartOfSpeech::Noun, "bˈO"), (PartOfSpeech::Verb, "bˈW")]),
+    ("subject", &[(PartOfSpeech::Noun, "sˈʌbʤɛkt"), (PartOfSpeech::Verb, "səbʤˈɛkt")]),
+    ("progress", &[(PartOfSpeech::Noun, "pɹˈɑɡɹɛs"), (PartOfSpeech::Verb, "pɹəɡɹˈɛs")]),
+    ("rebel", &[(PartOfSpeech::Noun, "ɹˈɛbᵊl"), (PartOfSpeech::Verb, "ɹəbˈɛl")]),
+    ("conflict", &[(PartOfSpeech::Noun, "kˈɑnflɪkt"), (PartOfSpeech::Verb, "kənflˈɪkt")]),
+    ("export", &[(PartOfSpeech::Noun, "ˈɛkspɔɹt"), (PartOfSpeech::Verb, "ɛkspˈɔɹt")]),
+    ("import", &[(PartOfSpeech::Noun, "ˈɪmpɔɹt"), (PartOfSpeech::Verb, "ɪmpˈɔɹt")]),
+    ("insult", &[(PartOfSpeech::Noun, "ˈɪnsʌlt"), (PartOfSpeech::Verb, "ɪnsˈʌlt")]),
+    ("suspect", &[(PartOfSpeech::Noun, "sˈʌspɛkt"), (PartOfSpeech::Verb, "səspˈɛkt")]),
+    ("separate", &[(PartOfSpeech::Adjective, "sˈɛpəɹət"), (PartOfSpeech::Verb, "sˈɛpəɹAt")]),
+    ("estimate", &[(PartOfSpeech::Noun, "ˈɛstəmət"), (PartOfSpeech::Verb, "ˈɛstəmAt")]),
+    ("minute", &[(PartOfSpeech::Noun, "mˈɪnət"), (PartOfSpeech::Adjective, "mInˈut")]),
+];
+
+/// A run of input text, either left for the G2P backend or already resolved
+/// to Misaki phonemes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Phonemes(String),
+}
+
+/// A word of the input text resolved to fixed Misaki phonemes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedWord {
+    /// Position among the words of the text (tokens with a letter or digit)
+    pub index: usize,
+    /// Byte span in the text
+    pub start: usize,
+    pub end: usize,
+    pub phonemes: String,
+}
+
+/// Punctuation in phoneme strings, which is not part of any word
+const PHONEME_PUNCTUATION: &str = ";:,.!?—…\"()“”";
+
+/// Picks context-dependent pronunciations for English homographs before
+/// phonemization, plus fixed per-word pronunciation overrides.
+#[derive(Clone)]
+pub struct HomographResolver {
+    tagger: PosTagger,
+    homographs: HashMap<String, Vec<(PartOfSpeech, String)>>,
+    pronunciations: HashMap<String, String>,
+}
+
+impl HomographResolver {
+    /// Resolver with the built-in homograph table
+    pub fn new() -> Self {
+        let mut resolver = Self::empty();
+        for (word, senses) in HOMOGRAPHS {
+            for (pos, phonemes) in senses.iter() {
+                resolver.insert_homograph(word, *pos, phonemes);
+            }
+        }
+        resolver
+    }
+
+    /// Resolver with no homographs, for pronunciation overrides only
+    pub fn empty() -> Self {
+        HomographResolver {
+            tagger: PosTagger::new(),
+            homographs: HashMap::new(),
+            pronunciations: HashMap::new(),
+        }
+    }
+
+    /// Add or replace the Misaki pronunciation of `word` used as `pos`.
+    pub fn insert_homograph(&mut self, word: &str, pos: PartOfSpeech, phonemes: &str) {
+        let senses = self.homographs.entry(word.to_lowercase()).or_default();
+        match senses.iter_mut().find(|(p, _)| *p == pos) {
+            Some(sense) => sense.1 = phonemes.to_string(),
+            None => senses.push((pos, phonemes.to_string())),
+        }
+    }
+
+    /// Always pronounce `word` as `phonemes` (Misaki), regardless of context.
+    pub fn insert_pronunciation(&mut self, word: &str, phonemes: &str) {
+        self.pronunciations
+            .insert(word.to_lowercase(), phonemes.to_string());
+    }
+
+    /// Words of the text with a fixed pronunciation or a homograph resolved
+    /// from its tagged sentence.
+    pub fn resolve_words(&self, text: &str) -> Vec<ResolvedWord> {
+        let tokens = tokens(text);
+        let mut resolved = Vec::new();
+        let mut word_index = 0;
+
+        for sentence in tokens.split_inclusive(|token| is_sentence_end(&text[token.0..token.1])) {
+            let words: Vec<&str> = sentence.iter().map(|&(s, e)| &text[s..e]).collect();
+            // Only sentences with a homograph need tagging
+            let mut tags = None;
+
+            for (index, &(start, end)) in sentence.iter().enumerate() {
+                if !is_word(words[index]) {
+                    continue;
+                }
+                let key = words[index].to_lowercase().replace('’', "'");
+                let phonemes = match self.pronunciations.get(&key) {
+                    Some(phonemes) => Some(phonemes.clone()),
+                    None if self.homographs.contains_key(&key) => {
+                        let tags = tags.get_or_insert_with(|| self.tagger.tag(&words));
+                        self.pronounce(&key, &words, tags, index)
+                    }
+                    None => None,
+                };
+                if let Some(phonemes) = phonemes {
+                    resolved.push(ResolvedWord {
+                        index: word_index,
+                        start,
+                        end,
+                        phonemes,
+                    });
+                }
+                word_index += 1;
+            }
+        }
+
+        resolved
+    }
+
+    fn pronounce(
+        &self,
+        word: &str,
+        words: &[&str],
+        tags: &[Option<Upos>],
+        index: usize,
+    ) -> Option<String> {
+        let senses = self.homographs.get(word)?;
+        let has_past = senses.iter().any(|(p, _)| *p == PartOfSpeech::PastTense);
+        let pos = self.tagger.part_of_speech(words, tags, index, has_past);
+
+        let find = |pos: PartOfSpeech| senses.iter().find(|(p, _)| *p == pos);
+        let fallback = |pos: PartOfSpeech| match pos {
+            PartOfSpeech::PastTense => PartOfSpeech::Verb,
+            PartOfSpeech::Adjective => PartOfSpeech::Noun,
+            PartOfSpeech::Noun => PartOfSpeech::Adjective,
+            PartOfSpeech::Verb => PartOfSpeech::Verb,
+        };
+        pos.and_then(|pos| find(pos).or_else(|| find(fallback(pos))))
+            .or_else(|| senses.first())
+            .map(|(_, phonemes)| phonemes.clone())
+    }
+}
+
+impl Default for HomographResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs of text between resolved words, and the resolved words themselves
+pub fn segments<'a>(text: &'a str, resolved: &[ResolvedWord]) -> Vec<Segment<'a>> {
+    let mut segments = Vec::new();
+    let mut last_end = 0;
+
+    for word in resolved {
+        if word.start > last_end {
+            segments.push(Segment::Text(&text[last_end..word.start]));
+        }
+        segments.push(Segment::Phonemes(word.phonemes.clone()));
+        last_end = word.end;
+    }
+
+    if last_end < text.len() {
+        segments.push(Segment::Text(&text[last_end..]));
+    }
+    segments
+}
+
+/// Replace the phonemes of resolved words in `phonemes`, the G2P output for
+/// the whole `text`, keeping any punctuation attached to them. Returns
+/// `None` when the phonemes don't split into one word per text word
+/// (numbers read as several words, words the backend merged).
+pub fn splice_phonemes(text: &str, phonemes: &str, resolved: &[ResolvedWord]) -> Option<String> {
+    let is_phoneme_word = |word: &str| word.chars().any(|c| !PHONEME_PUNCTUATION.contains(c));
+    let word_count = tokens(text)
+        .iter()
+        .filter(|&&(s, e)| is_word(&text[s..e]))
+        .count();
+    let phoneme_words: Vec<&str> = phonemes.split(' ').collect();
+    if phoneme_words
+        .iter()
+        .filter(|word| is_phoneme_word(word))
+        .count()
+        != word_count
+    {
+        return None;
+    }
+
+    let mut resolved = resolved.iter().peekable();
+    let mut word_index = 0;
+    let spliced: Vec<String> = phoneme_words
+        .into_iter()
+        .map(|word| {
+            if !is_phoneme_word(word) {
+                return word.to_string();
+            }
+            let replaced = match resolved.next_if(|r| r.index == word_index) {
+                Some(r) => {
+                    let core_start = word.len()
+                        - word
+                            .trim_start_matches(|c| PHONEME_PUNCTUATION.contains(c))
+                            .len();
+                    let core_end = word
+                        .trim_end_matches(|c| PHONEME_PUNCTUATION.contains(c))
+                        .len();
+                    format!("{}{}{}", &word[..core_start], r.phonemes, &word[core_end..])
+                }
+                None => word.to_string(),
+            };
+            word_index += 1;
+            replaced
+        })
+        .collect();
+    Some(spliced.join(" "))
+}
+
+/// Byte spans of the tokens in `text`: whitespace-separated chunks with
+/// leading and trailing punctuation split off one mark at a time, the way
+/// the tagger's training data was tokenized ("don't" and "well-known" stay
+/// whole).
+fn tokens(text: &str) -> Vec<(usize, usize)> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+
+    for chunk in text.split_inclusive(char::is_whitespace) {
+        let chunk_start = offset;
+        offset += chunk.len();
+        let chunk = chunk.trim_end();
+
+        let core_start = chunk.len()
+            - chunk
+                .trim_start_matches(|c: char| !c.is_alphanumeric())
+                .len();
+        let core_end = chunk
+            .trim_end_matches(|c: char| !c.is_alphanumeric())
+            .len()
+            .max(core_start);
+        let punctuation = |from: usize, to: usize, tokens: &mut Vec<(usize, usize)>| {
+            for (i, c) in chunk[from..to].char_indices() {
+                let start = chunk_start + from + i;
+                tokens.push((start, start + c.len_utf8()));
+            }
+        };
+
+        punctuation(0, core_start, &mut tokens);
+        if core_end > core_start {
+            tokens.push((chunk_start + core_start, chunk_start + core_end));
+        }
+        punctuation(core_end, chunk.len(), &mut tokens);
+    }
+
+    tokens
+}
+
+fn is_word(token: &str) -> bool {
+    token.chars().any(char::is_alphanumeric)
+}
+
+fn is_sentence_end(token: &str) -> bool {
+    matches!(token, "." | "!" | "?" | "…")
+}
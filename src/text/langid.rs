@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+/// Languages the sentence-level detector can tag, with the espeak-ng voice and
+/// Misaki mapping each one is phonemized with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    /// American English (Kokoro `a*` voices)
+    EnglishUs,
+    /// Spanish (Kokoro `e*` voices)
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::EnglishUs, Language::Spanish];
+
+    /// espeak-ng voice used to phonemize sentences in this language
+    pub fn espeak_voice(&self) -> &'static str {
+        match self {
+            Language::EnglishUs => "en-us",
+            Language::Spanish => "es",
+        }
+    }
+
+    fn profile(&self) -> &'static str {
+        match self {
+            Language::EnglishUs => include_str!("profiles/en.txt"),
+            Language::Spanish => include_str!("profiles/es.txt"),
+        }
+    }
+}
+
+/// Trigram counts for one language, loaded from the bundled profile files
+struct TrigramProfile {
+    counts: HashMap<String, u32>,
+    total: u32,
+}
+
+impl TrigramProfile {
+    fn parse(content: &str) -> Self {
+        let mut counts = HashMap::new();
+        let mut total = 0;
+
+        for line in content.lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            if let Some((trigram, count)) = line.split_once('\t') {
+                if let Ok(count) = count.trim().parse::<u32>() {
+                    counts.insert(trigram.replace('_', " "), count);
+                    total += count;
+                }
+            }
+        }
+
+        TrigramProfile { counts, total }
+    }
+
+    /// Smoothed log-probability of a trigram under this profile
+    fn log_probability(&self, trigram: &str, vocabulary_size: u32) -> f64 {
+        let count = self.counts.get(trigram).copied().unwrap_or(0);
+        ((count + 1) as f64 / (self.total + vocabulary_size) as f64).ln()
+    }
+}
+
+/// Offline sentence language detector using character trigram profiles
+/// bundled with the crate (naive Bayes over word-padded trigrams).
+pub struct LanguageDetector {
+    profiles: Vec<(Language, TrigramProfile)>,
+    vocabulary_size: u32,
+}
+
+impl LanguageDetector {
+    pub fn new() -> Self {
+        let profiles: Vec<(Language, TrigramProfile)> = Language::ALL
+            .iter()
+            .map(|&language| (language, TrigramProfile::parse(language.profile())))
+            .collect();
+
+        let vocabulary_size = profiles
+            .iter()
+            .flat_map(|(_, profile)| profile.counts.keys())
+            .collect::<std::collections::HashSet<_>>()
+            .len() as u32;
+
+        LanguageDetector {
+            profiles,
+            vocabulary_size,
+        }
+    }
+
+    /// Most likely language of the text; American English when it has no letters.
+    pub fn detect(&self, text: &str) -> Language {
+        self.scores(text)
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(language, _)| language)
+            .unwrap_or(Language::EnglishUs)
+    }
+
+    /// Log-likelihood of the text under each language profile. Empty when the
+    /// text contains no letters.
+    pub fn scores(&self, text: &str) -> Vec<(Language, f64)> {
+        let trigrams = extract_trigrams(text);
+        if trigrams.is_empty() {
+            return Vec::new();
+        }
+
+        self.profiles
+            .iter()
+            .map(|(language, profile)| {
+                let score = trigrams
+                    .iter()
+                    .map(|trigram| profile.log_probability(trigram, self.vocabulary_size))
+                    .sum();
+                (*language, score)
+            })
+            .collect()
+    }
+}
+
+impl Default for LanguageDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lowercased trigrams of each word, padded with a space on both sides
+fn extract_trigrams(text: &str) -> Vec<String> {
+    let lowercase = text.to_lowercase();
+    let mut trigrams = Vec::new();
+
+    for word in lowercase.split(|c: char| !c.is_alphabetic()) {
+        if word.is_empty() {
+            continue;
+        }
+        let padded: Vec<char> = format!(" {} ", word).chars().collect();
+        for window in padded.windows(3) {
+            trigrams.push(window.iter().collect());
+        }
+    }
+
+    trigrams
+}
@@ -0,0 +1,9 @@
+pub mod homographs;
+pub mod langid;
+pub mod pos;
+pub mod sentences;
+
+pub use homographs::{segments, splice_phonemes, HomographResolver, Segment};
+pub use langid::{Language, LanguageDetector};
+pub use pos::PartOfSpeech;
+pub use sentences::split_sentences;
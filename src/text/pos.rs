@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Coarse part of speech used to pick a homograph pronunciation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartOfSpeech {
+    Noun,
+    Verb,
+    /// Past tense or past participle ("I read it yesterday", "has been read")
+    PastTense,
+    Adjective,
+}
+
+/// Universal Dependencies part-of-speech tag, as assigned by `PosTagger`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Upos {
+    Adj,
+    Adp,
+    Adv,
+    Aux,
+    Cconj,
+    Det,
+    Intj,
+    Noun,
+    Num,
+    Part,
+    Pron,
+    Propn,
+    Punct,
+    Sconj,
+    Sym,
+    Verb,
+    X,
+}
+
+impl Upos {
+    fn parse(tag: &str) -> Option<Self> {
+        Some(match tag {
+            "ADJ" => Upos::Adj,
+            "ADP" => Upos::Adp,
+            "ADV" => Upos::Adv,
+            "AUX" => Upos::Aux,
+            "CCONJ" => Upos::Cconj,
+            "DET" => Upos::Det,
+            "INTJ" => Upos::Intj,
+            "NOUN" => Upos::Noun,
+            "NUM" => Upos::Num,
+            "PART" => Upos::Part,
+            "PRON" => Upos::Pron,
+            "PROPN" => Upos::Propn,
+            "PUNCT" => Upos::Punct,
+            "SCONJ" => Upos::Sconj,
+            "SYM" => Upos::Sym,
+            "VERB" => Upos::Verb,
+            "X" => Upos::X,
+            _ => return None,
+        })
+    }
+}
+
+/// Condition of a transformation rule, relative to the token being retagged.
+/// The learned rules depend on their trainer's semantics, which are kept
+/// as is (`Word` matches a prefix, `AnyTag` excludes its far end).
+enum Condition {
+    /// The token `offset` away has this tag
+    Tag { offset: isize, tag: Upos },
+    /// A token in `[i, i + offset)` (or `[i + offset, i)`) has this tag
+    AnyTag { offset: isize, tag: Upos },
+    /// The previous and next tokens have these tags
+    Between { previous: Upos, next: Upos },
+    /// The token `offset` away starts with this text, ignoring ASCII case
+    Word { offset: isize, prefix: String },
+    /// The token `offset` away is one of these lowercase words
+    OneOf { offset: isize, words: Vec<String> },
+    /// The token starts the sentence or follows punctuation
+    ClauseStart,
+}
+
+impl Condition {
+    fn parse(condition: &str) -> Option<Self> {
+        if condition == "start" {
+            return Some(Condition::ClauseStart);
+        }
+        let mut parts = condition.splitn(3, ':');
+        let kind = parts.next()?;
+        let first = parts.next()?;
+        let second = parts.next()?;
+        Some(match kind {
+            "tag" => Condition::Tag {
+                offset: first.parse().ok()?,
+                tag: Upos::parse(second)?,
+            },
+            "any" => Condition::AnyTag {
+                offset: first.parse().ok()?,
+                tag: Upos::parse(second)?,
+            },
+            "between" => Condition::Between {
+                previous: Upos::parse(first)?,
+                next: Upos::parse(second)?,
+            },
+            "word" => Condition::Word {
+                offset: first.parse().ok()?,
+                prefix: second.to_string(),
+            },
+            "is" => Condition::OneOf {
+                offset: first.parse().ok()?,
+                words: second.split('|').map(str::to_string).collect(),
+            },
+            _ => return None,
+        })
+    }
+
+    fn holds(&self, tokens: &[&str], tags: &[Option<Upos>], index: usize) -> bool {
+        let tag_at = |i: Option<usize>, tag: Upos| {
+            i.and_then(|i| tags.get(i).copied().flatten()) == Some(tag)
+        };
+        match self {
+            Condition::Tag { offset, tag } => tag_at(index.checked_add_signed(*offset), *tag),
+            Condition::AnyTag { offset, tag } => match index.checked_add_signed(*offset) {
+                Some(far) => (far.min(index)..far.max(index)).any(|i| tag_at(Some(i), *tag)),
+                None => false,
+            },
+            Condition::Between { previous, next } => {
+                tag_at(index.checked_sub(1), *previous) && tag_at(Some(index + 1), *next)
+            }
+            Condition::Word { offset, prefix } => index
+                .checked_add_signed(*offset)
+                .and_then(|i| tokens.get(i))
+                .is_some_and(|token| {
+                    token
+                        .chars()
+                        .zip(prefix.chars())
+                        .all(|(a, b)| a.eq_ignore_ascii_case(&b))
+                }),
+            Condition::OneOf { offset, words } => index
+                .checked_add_signed(*offset)
+                .and_then(|i| tokens.get(i))
+                .is_some_and(|token| {
+                    let token = token.to_lowercase().replace('’', "'");
+                    words.contains(&token)
+                }),
+            Condition::ClauseStart => {
+                index == 0 || tags.get(index - 1).copied().flatten() == Some(Upos::Punct)
+            }
+        }
+    }
+}
+
+/// Retag a token from one tag to another when all conditions hold
+struct Rule {
+    from: Upos,
+    to: Upos,
+    conditions: Vec<Condition>,
+}
+
+struct TaggerModel {
+    /// Most frequent tag of each lowercase word
+    lexicon: HashMap<String, Upos>,
+    rules: Vec<Rule>,
+}
+
+impl TaggerModel {
+    fn parse(lexicon: &str, rules: &str) -> Self {
+        let lexicon = lexicon
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let (word, tag) = line.split_once('\t')?;
+                Some((word.to_string(), Upos::parse(tag.trim())?))
+            })
+            .collect();
+
+        let rules = rules
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let from = Upos::parse(fields.next()?)?;
+                let to = Upos::parse(fields.next()?)?;
+                let conditions = fields.map(Condition::parse).collect::<Option<Vec<_>>>()?;
+                Some(Rule {
+                    from,
+                    to,
+                    conditions,
+                })
+            })
+            .collect();
+
+        TaggerModel { lexicon, rules }
+    }
+}
+
+/// Bundled model, parsed on first use and shared by every tagger
+fn model() -> &'static TaggerModel {
+    static MODEL: OnceLock<TaggerModel> = OnceLock::new();
+    MODEL.get_or_init(|| {
+        TaggerModel::parse(
+            include_str!("tagger/lexicon.txt"),
+            concat!(
+                include_str!("tagger/rules.txt"),
+                include_str!("tagger/local_rules.txt")
+            ),
+        )
+    })
+}
+
+/// Auxiliaries after which a verb is a past participle ("has read", "was read")
+const PERFECT_AND_PASSIVE: &[&str] = &[
+    "have", "has", "had", "having", "i've", "you've", "we've", "they've", "be", "been", "being",
+    "is", "are", "was", "were", "am",
+];
+
+/// Past forms of auxiliaries and common irregular verbs
+const PAST_FORMS: &[&str] = &[
+    "was", "were", "had", "did", "wasn't", "weren't", "hadn't", "didn't", "went", "said", "saw",
+    "told", "got", "made", "took", "came", "knew", "thought", "found", "gave", "left", "felt",
+    "wrote", "heard", "began", "brought", "bought", "sat", "stood",
+];
+
+/// Words that put a clause in the past on their own
+const PAST_CUES: &[&str] = &["yesterday", "ago"];
+
+/// Offline part-of-speech tagger for homograph disambiguation: a
+/// transformation-based (Brill) tagger with a bundled lexicon and learned
+/// rules, trained on Universal Dependencies English data by the Harper
+/// project, followed by a few hand-written rules for contexts the learned
+/// ones miss around homographs. Tense is not part of the tag set;
+/// `part_of_speech` derives it from the tagged context.
+#[derive(Clone, Default)]
+pub struct PosTagger;
+
+impl PosTagger {
+    pub fn new() -> Self {
+        PosTagger
+    }
+
+    /// Tag one sentence. Tokens are words (contractions kept whole) and
+    /// single punctuation marks; words missing from the lexicon stay
+    /// untagged.
+    pub fn tag(&self, tokens: &[&str]) -> Vec<Option<Upos>> {
+        let model = model();
+        let mut tags: Vec<Option<Upos>> = tokens
+            .iter()
+            .map(|token| model.lexicon.get(&token.to_lowercase()).copied())
+            .collect();
+
+        for rule in &model.rules {
+            for index in 0..tokens.len() {
+                if tags[index] == Some(rule.from)
+                    && rule
+                        .conditions
+                        .iter()
+                        .all(|condition| condition.holds(tokens, &tags, index))
+                {
+                    tags[index] = Some(rule.to);
+                }
+            }
+        }
+
+        tags
+    }
+
+    /// Part of speech of `tokens[index]` given the sentence's tags. `has_past`
+    /// says whether the homograph has a past-tense pronunciation;
+    /// `PastTense` is only proposed for words that do.
+    pub fn part_of_speech(
+        &self,
+        tokens: &[&str],
+        tags: &[Option<Upos>],
+        index: usize,
+        has_past: bool,
+    ) -> Option<PartOfSpeech> {
+        match tags[index]? {
+            Upos::Noun | Upos::Propn => Some(PartOfSpeech::Noun),
+            // Adverbs take the adjective's pronunciation ("play live", "stay close")
+            Upos::Adj | Upos::Adv => Some(PartOfSpeech::Adjective),
+            Upos::Verb | Upos::Aux if has_past && is_past(tokens, tags, index) => {
+                Some(PartOfSpeech::PastTense)
+            }
+            Upos::Verb | Upos::Aux => Some(PartOfSpeech::Verb),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the verb at `index` is a past form: a participle after have or
+/// be, a bare form after a third-person singular subject, or a finite verb
+/// in a clause whose other verbs are past.
+fn is_past(tokens: &[&str], tags: &[Option<Upos>], index: usize) -> bool {
+    let lower = |i: usize| tokens[i].to_lowercase().replace('’', "'");
+
+    // Skip adverbs and negation between the verb and what governs it
+    let previous = (0..index)
+        .rev()
+        .find(|&i| tags[i] != Some(Upos::Adv) && lower(i) != "not");
+    if let Some(previous) = previous {
+        let word = lower(previous);
+        if PERFECT_AND_PASSIVE.contains(&word.as_str()) {
+            return true;
+        }
+        // Modals, do-support and "to" take the base form
+        if matches!(tags[previous], Some(Upos::Aux | Upos::Part)) {
+            return false;
+        }
+        // The present would be "reads"
+        if matches!(word.as_str(), "he" | "she" | "it") || tags[previous] == Some(Upos::Propn) {
+            return true;
+        }
+    }
+
+    let clause_start = (0..index)
+        .rev()
+        .find(|&i| tags[i] == Some(Upos::Punct))
+        .map_or(0, |i| i + 1);
+    let clause_end = (index + 1..tokens.len())
+        .find(|&i| tags[i] == Some(Upos::Punct))
+        .unwrap_or(tokens.len());
+    (clause_start..clause_end).any(|i| {
+        let word = lower(i);
+        i != index
+            && (PAST_CUES.contains(&word.as_str())
+                || match tags[i] {
+                    Some(Upos::Aux) => PAST_FORMS.contains(&word.as_str()),
+                    Some(Upos::Verb) => {
+                        PAST_FORMS.contains(&word.as_str())
+                            || (word.ends_with("ed") && !word.ends_with("eed"))
+                    }
+                    _ => false,
+                })
+    })
+}
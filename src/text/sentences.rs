@@ -0,0 +1,83 @@
+/// Abbreviations whose trailing period does not end a sentence
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "sra", "srta", "st", "vs", "etc", "e.g", "i.e", "a.m",
+    "p.m",
+];
+
+/// Split text into sentences on `.`, `!`, `?`, `…` (and their full-width forms),
+/// keeping the terminating punctuation and any closing quotes with the sentence.
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if !is_terminator(c) {
+            continue;
+        }
+
+        // Absorb runs of punctuation and closing quotes ("Really?!", "end.”)
+        let mut end = index + c.len_utf8();
+        while let Some(&(next_index, next)) = chars.peek() {
+            if is_terminator(next) || is_closing_quote(next) {
+                end = next_index + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let at_boundary = match chars.peek() {
+            Some(&(_, next)) => next.is_whitespace() || is_fullwidth_terminator(c),
+            None => true,
+        };
+        if !at_boundary || (c == '.' && ends_with_abbreviation(&text[start..index])) {
+            continue;
+        }
+
+        push_trimmed(&mut sentences, &text[start..end]);
+        start = end;
+    }
+
+    push_trimmed(&mut sentences, &text[start..]);
+    sentences
+}
+
+fn push_trimmed<'a>(sentences: &mut Vec<&'a str>, sentence: &'a str) {
+    let sentence = sentence.trim();
+    if !sentence.is_empty() {
+        sentences.push(sentence);
+    }
+}
+
+fn is_terminator(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '…') || is_fullwidth_terminator(c)
+}
+
+fn is_fullwidth_terminator(c: char) -> bool {
+    matches!(c, '。' | '！' | '？')
+}
+
+fn is_closing_quote(c: char) -> bool {
+    matches!(c, '"' | '\'' | '”' | '’' | '»' | ')' | '」' | '』')
+}
+
+fn ends_with_abbreviation(text: &str) -> bool {
+    let last_word = text
+        .rsplit(|c: char| c.is_whitespace())
+        .next()
+        .unwrap_or("");
+    ABBREVIATIONS.contains(&last_word.to_lowercase().as_str()) || is_initialism(last_word)
+}
+
+/// Capital initials such as "U.S" or "J.R.R" (the final period is the
+/// candidate terminator). A lone capital is not enough: "So do I. Yes."
+fn is_initialism(word: &str) -> bool {
+    let mut parts = word.split('.');
+    let count = parts.clone().count();
+    count >= 2
+        && parts.all(|part| {
+            let mut chars = part.chars();
+            matches!((chars.next(), chars.next()), (Some(c), None) if c.is_uppercase())
+        })
+}